@@ -0,0 +1,76 @@
+//! Times the programs of day 24 interpreted by the ALU, compiled as they
+//! are, and optimized then compiled:
+//!
+//! ```text
+//! cargo run --release --example alu [FILE]
+//! ```
+
+use std::env;
+use std::hint::black_box;
+use std::process;
+
+use aoc::bench::{self, print_stats, Runs};
+use aoc::day24::{self, Alu, ModelNumbers, Order, Program, Register};
+use aoc::Source;
+
+fn main() {
+    let source = match env::args().nth(1) {
+        Some(path) => Source::File(path.into()),
+        None => Source::named("input"),
+    };
+    let program = aoc::read_input(24, &source)
+        .and_then(|input| day24::parse_program(&input))
+        .unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        });
+    let compiled = Program::compile(&program);
+    let optimized = Program::optimize(&program, &[Register::Z]);
+    println!(
+        "{} instructions, {} once optimized",
+        program.len(),
+        optimized.len()
+    );
+
+    // every model number from 11111111111111 in steps of 1234567
+    let numbers: Vec<Vec<i64>> = (0..10_000u64)
+        .map(|i| 11_111_111_111_111 + i * 1_234_567)
+        .map(|n| n.to_string().bytes().map(|b| (b - b'0') as i64).collect())
+        .collect();
+    let runs = Runs::default();
+    let run = |f: &dyn Fn(&[i64]) -> i64| {
+        bench::time(runs, || {
+            black_box(&numbers)
+                .iter()
+                .map(|n| f(black_box(n)))
+                .sum::<i64>()
+        })
+    };
+    println!();
+    println!("Run {} model numbers", numbers.len());
+    let interpreted =
+        run(&|n| Alu::new(&program).run(n.to_vec()).map_or(0, |r| r.z));
+    print_stats("interpreted", interpreted, interpreted);
+    let stats = run(&|n| compiled.run(n.to_vec()).map_or(0, |r| r.z));
+    print_stats("compiled", stats, interpreted);
+    let stats = run(&|n| optimized.run(n.to_vec()).map_or(0, |r| r.z));
+    print_stats("optimized", stats, interpreted);
+
+    println!();
+    println!("Search the largest model number");
+    // the search only ends in time on MONAD when it skips large values of z
+    let z_limit = 1_000_000;
+    let runs = Runs::exactly(3);
+    let unoptimized = bench::time(runs, || {
+        ModelNumbers::new(black_box(&compiled), Order::Descending)
+            .with_z_limit(z_limit)
+            .next()
+    });
+    print_stats("compiled", unoptimized, unoptimized);
+    let stats = bench::time(runs, || {
+        ModelNumbers::new(black_box(&optimized), Order::Descending)
+            .with_z_limit(z_limit)
+            .next()
+    });
+    print_stats("optimized", stats, unoptimized);
+}
@@ -0,0 +1,42 @@
+//! Times the searches of day 23, Dijkstra's algorithm against A*, on the
+//! folded and unfolded burrows:
+//!
+//! ```text
+//! cargo run --release --example amphipod [FILE]
+//! ```
+
+use std::env;
+use std::hint::black_box;
+use std::process;
+
+use aoc::bench::{self, print_stats, Runs};
+use aoc::day23::{self, Burrow};
+use aoc::Source;
+
+fn main() {
+    let source = match env::args().nth(1) {
+        Some(path) => Source::File(path.into()),
+        None => Source::named("input"),
+    };
+    let burrow = aoc::read_input(23, &source)
+        .and_then(|input| Burrow::parse(&input))
+        .and_then(|burrow| Ok((day23::unfold(&burrow)?, burrow)))
+        .unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        });
+    let (unfolded, folded) = burrow;
+
+    let runs = Runs::exactly(5);
+    for (i, burrow) in [folded, unfolded].iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("Depth {}", burrow.depth());
+        let dijkstra =
+            bench::time(runs, || day23::dijkstra(black_box(burrow)));
+        print_stats("dijkstra", dijkstra, dijkstra);
+        let stats = bench::time(runs, || day23::astar(black_box(burrow)));
+        print_stats("a*", stats, dijkstra);
+    }
+}
@@ -0,0 +1,336 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The expected answers to the puzzles, keyed by day and part.
+///
+/// They are stored in the same format as the output of `aoc`, so that a
+/// run can be pasted into the file as is. An answer spanning several lines
+/// continues until the next blank line.
+///
+/// ```text
+/// --- Day 1: Sonar Sweep ---
+/// Part One: 1688
+/// Part Two: 1728
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Answers {
+    answers: BTreeMap<(u8, u8), String>,
+}
+
+/// The outcome of comparing an answer with the expected one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Check {
+    Pass,
+    Fail,
+    Missing,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Check::Pass => "PASS",
+            Check::Fail => "FAIL",
+            Check::Missing => "MISSING",
+        };
+        f.pad(s)
+    }
+}
+
+impl Answers {
+    /// Reads the answers, ignoring every line that is neither a day
+    /// header, a part nor the continuation of a part.
+    pub fn parse(text: &str) -> Answers {
+        Answers::parse_from(None, text)
+    }
+
+    /// Reads the answers of a single day, whose header may be left out.
+    pub fn parse_day(day: u8, text: &str) -> Answers {
+        Answers::parse_from(Some(day), text)
+    }
+
+    fn parse_from(mut day: Option<u8>, text: &str) -> Answers {
+        let mut answers = Answers::default();
+        let mut last = None;
+        for line in text.lines() {
+            if let Some(header) = line.strip_prefix("--- Day ") {
+                let mut digits = header.split(|c: char| !c.is_ascii_digit());
+                day = digits.next().and_then(|d| d.parse().ok());
+                last = None;
+                continue;
+            }
+            let (part, answer) = match line.split_once(':') {
+                Some(("Part One", answer)) => (1, answer),
+                Some(("Part Two", answer)) => (2, answer),
+                _ => {
+                    match last {
+                        Some(key) if !line.trim().is_empty() => {
+                            let answer = answers.answers.entry(key);
+                            let answer = answer.or_default();
+                            if !answer.is_empty() {
+                                answer.push('\n');
+                            }
+                            answer.push_str(line.trim_end());
+                        }
+                        _ => last = None,
+                    }
+                    continue;
+                }
+            };
+            if let Some(day) = day {
+                answers.insert(day, part, answer.trim_start());
+                last = Some((day, part));
+            }
+        }
+        answers
+    }
+
+    pub fn get(&self, day: u8, part: u8) -> Option<&str> {
+        self.answers.get(&(day, part)).map(|s| s.as_str())
+    }
+
+    pub fn insert(&mut self, day: u8, part: u8, answer: &str) {
+        self.answers.insert((day, part), normalize(answer));
+    }
+
+    pub fn len(&self) -> usize {
+        self.answers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.answers.is_empty()
+    }
+
+    pub fn check(&self, day: u8, part: u8, answer: &str) -> Check {
+        match self.get(day, part) {
+            Some(expected) if expected == normalize(answer) => Check::Pass,
+            Some(_) => Check::Fail,
+            None => Check::Missing,
+        }
+    }
+}
+
+impl fmt::Display for Answers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut day = None;
+        for (&(d, part), answer) in &self.answers {
+            if day != Some(d) {
+                if day.is_some() {
+                    writeln!(f)?;
+                }
+                let title = crate::solution(d).map_or("", |s| s.title());
+                writeln!(f, "--- Day {}: {} ---", d, title)?;
+                day = Some(d);
+            }
+            write_part(f, part, answer)?;
+        }
+        Ok(())
+    }
+}
+
+fn write_part(
+    f: &mut impl fmt::Write,
+    part: u8,
+    answer: &str,
+) -> fmt::Result {
+    let name = if part == 1 { "One" } else { "Two" };
+    match answer.contains('\n') {
+        true => writeln!(f, "Part {}:\n{}", name, answer),
+        false => writeln!(f, "Part {}: {}", name, answer),
+    }
+}
+
+/// Where the expected answers are kept: in a single file for every day,
+/// or in a file per day, `NN-NAME.answers` in a directory, without a day
+/// header like the examples.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnswerFiles {
+    Single(PathBuf),
+    PerDay { dir: PathBuf, name: String },
+}
+
+impl AnswerFiles {
+    /// The file holding the answers of the day.
+    pub fn path(&self, day: u8) -> PathBuf {
+        match self {
+            AnswerFiles::Single(path) => path.clone(),
+            AnswerFiles::PerDay { dir, name } => {
+                dir.join(format!("{:02}-{}.answers", day, name))
+            }
+        }
+    }
+
+    /// Reads the answers of the days. A day without a file of its own has
+    /// no answers, while a missing single file is an error unless
+    /// `missing_ok`.
+    pub fn read(
+        &self,
+        days: impl IntoIterator<Item = u8>,
+        missing_ok: bool,
+    ) -> io::Result<Answers> {
+        let mut answers = Answers::default();
+        match self {
+            AnswerFiles::Single(path) => {
+                if let Some(text) = read_file(path, missing_ok)? {
+                    answers = Answers::parse(&text);
+                }
+            }
+            AnswerFiles::PerDay { .. } => {
+                for day in days {
+                    if let Some(text) = read_file(&self.path(day), true)? {
+                        let day = Answers::parse_day(day, &text);
+                        answers.answers.extend(day.answers);
+                    }
+                }
+            }
+        }
+        Ok(answers)
+    }
+
+    /// Writes the answers, to the file of every day they are for if there
+    /// is a file per day.
+    pub fn write(&self, answers: &Answers) -> io::Result<()> {
+        match self {
+            AnswerFiles::Single(path) => {
+                write_file(path, &answers.to_string())
+            }
+            AnswerFiles::PerDay { .. } => {
+                let mut days: BTreeMap<u8, String> = BTreeMap::new();
+                for (&(day, part), answer) in &answers.answers {
+                    let text = days.entry(day).or_default();
+                    let _ = write_part(text, part, answer);
+                }
+                for (day, text) in days {
+                    write_file(&self.path(day), &text)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for AnswerFiles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = match self {
+            AnswerFiles::Single(path) => path.clone(),
+            AnswerFiles::PerDay { dir, name } => {
+                dir.join(format!("NN-{}.answers", name))
+            }
+        };
+        write!(f, "{}", path.display())
+    }
+}
+
+fn read_file(path: &Path, missing_ok: bool) -> io::Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(Some(text)),
+        Err(err) if missing_ok && err.kind() == io::ErrorKind::NotFound => {
+            Ok(None)
+        }
+        Err(err) => {
+            let msg = format!("cannot read {}: {}", path.display(), err);
+            Err(io::Error::new(err.kind(), msg))
+        }
+    }
+}
+
+fn write_file(path: &Path, text: &str) -> io::Result<()> {
+    fs::write(path, text).map_err(|err| {
+        let msg = format!("cannot write {}: {}", path.display(), err);
+        io::Error::new(err.kind(), msg)
+    })
+}
+
+/// Strips the blank lines around an answer and the trailing spaces of its
+/// lines, which do not survive being pasted around.
+fn normalize(answer: &str) -> String {
+    let lines: Vec<&str> = answer.lines().map(str::trim_end).collect();
+    let start = lines.iter().position(|s| !s.is_empty());
+    let end = lines.iter().rposition(|s| !s.is_empty());
+    match (start, end) {
+        (Some(start), Some(end)) => lines[start..=end].join("\n"),
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ANSWERS: &str = "\
+--- Day 1: Sonar Sweep ---
+Part One: 1688
+Part Two: 1728
+
+--- Day 13: Transparent Origami ---
+Part One: 17
+Part Two:
+#####
+#   #
+#####
+
+--- Day 25: Sea Cucumber ---
+Part One: 471
+Part Two: Sleigh keys detected!
+";
+
+    #[test]
+    fn parse() {
+        let answers = Answers::parse(ANSWERS);
+        assert_eq!(answers.len(), 6);
+        assert_eq!(answers.get(13, 2), Some("#####\n#   #\n#####"));
+        assert_eq!(answers.get(1, 2), Some("1728"));
+        assert_eq!(answers.get(25, 2), Some("Sleigh keys detected!"));
+        assert_eq!(answers.get(2, 1), None);
+        assert_eq!(answers.to_string(), ANSWERS);
+    }
+
+    #[test]
+    fn check() {
+        let answers = Answers::parse("--- Day 1 ---\nPart One: 7\n\nnoise\n");
+        assert_eq!(answers.check(1, 1, "7"), Check::Pass);
+        assert_eq!(answers.check(1, 1, "5"), Check::Fail);
+        assert_eq!(answers.check(1, 2, "5"), Check::Missing);
+
+        let answers = Answers::parse_day(4, "Part Two: 1924\n");
+        assert_eq!(answers.check(4, 2, "1924"), Check::Pass);
+        assert_eq!(answers.check(4, 1, "4512"), Check::Missing);
+
+        let mut answers = Answers::default();
+        answers.insert(13, 2, "\n#####\n#   # \n#####\n");
+        assert_eq!(answers.check(13, 2, "#####\n#   #\n#####"), Check::Pass);
+    }
+
+    #[test]
+    fn answer_files() {
+        let dir = std::env::temp_dir().join("aoc-answer-files");
+        let _ = fs::create_dir_all(&dir);
+        let name = "example".to_string();
+        let files = AnswerFiles::PerDay {
+            dir: dir.clone(),
+            name,
+        };
+        assert_eq!(files.path(4), dir.join("04-example.answers"));
+        assert_eq!(
+            files.to_string(),
+            dir.join("NN-example.answers").display().to_string()
+        );
+
+        let _ = fs::remove_file(files.path(13));
+        let mut answers = Answers::default();
+        answers.insert(1, 1, "7");
+        answers.insert(1, 2, "5");
+        answers.insert(13, 2, "#####\n#   #\n#####");
+        files.write(&answers).unwrap();
+        let text = fs::read_to_string(files.path(1)).unwrap();
+        assert_eq!(text, "Part One: 7\nPart Two: 5\n");
+        assert_eq!(files.read([1, 13], false).unwrap(), answers);
+        assert_eq!(files.read([1, 2], false).unwrap().len(), 2);
+
+        let files = AnswerFiles::Single(dir.join("missing.txt"));
+        assert!(files.read([1], false).is_err());
+        assert!(files.read([1], true).unwrap().is_empty());
+    }
+}
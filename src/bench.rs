@@ -0,0 +1,178 @@
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use crate::{Parsed, Result, Solution};
+
+/// How many times each phase of a puzzle is run.
+#[derive(Debug, Clone, Copy)]
+pub struct Runs {
+    pub min: usize,
+    pub max: usize,
+    /// Stop sampling a phase after this long, once `min` runs are done.
+    pub budget: Duration,
+}
+
+impl Default for Runs {
+    fn default() -> Runs {
+        Runs {
+            min: 3,
+            max: 100,
+            budget: Duration::from_secs(1),
+        }
+    }
+}
+
+impl Runs {
+    /// Runs each phase exactly `n` times.
+    pub fn exactly(n: usize) -> Runs {
+        Runs {
+            min: n,
+            max: n,
+            budget: Duration::ZERO,
+        }
+    }
+}
+
+/// The wall time statistics of one phase, all zero if it was not run.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        let mut samples = samples.to_vec();
+        samples.sort_unstable();
+        let runs = samples.len();
+        let total: Duration = samples.iter().sum();
+        Stats {
+            runs,
+            min: samples.first().copied().unwrap_or_default(),
+            median: match runs {
+                0 => Duration::ZERO,
+                n if n % 2 == 0 => (samples[n / 2 - 1] + samples[n / 2]) / 2,
+                n => samples[n / 2],
+            },
+            mean: total.checked_div(runs as u32).unwrap_or_default(),
+        }
+    }
+
+    /// How many times faster the median run is than that of the baseline.
+    pub fn speedup(&self, baseline: &Stats) -> f64 {
+        baseline.median.as_secs_f64()
+            / self.median.max(Duration::from_nanos(1)).as_secs_f64()
+    }
+}
+
+/// The timings of parsing and solving the input of one puzzle.
+pub struct Report {
+    pub day: u8,
+    pub title: &'static str,
+    pub parse: Stats,
+    pub part_one: Stats,
+    pub part_two: Stats,
+}
+
+impl Report {
+    /// The sum of the median times of all the phases.
+    pub fn total(&self) -> Duration {
+        self.parse.median + self.part_one.median + self.part_two.median
+    }
+}
+
+fn sample<T>(runs: Runs, mut f: impl FnMut() -> Result<T>) -> Result<Stats> {
+    let start = Instant::now();
+    let mut samples = Vec::new();
+    while samples.len() < runs.max
+        && (samples.len() < runs.min || start.elapsed() < runs.budget)
+    {
+        let now = Instant::now();
+        black_box(f()?);
+        samples.push(now.elapsed());
+    }
+    Ok(Stats::from_samples(&samples))
+}
+
+/// Runs `f` repeatedly, to time code other than the phases of a puzzle.
+/// Its results are kept from being optimized away, but not its inputs,
+/// which `f` should pass through [`black_box`] itself.
+pub fn time<T>(runs: Runs, mut f: impl FnMut() -> T) -> Stats {
+    let stats: Result<Stats> = sample(runs, || Ok(black_box(f())));
+    stats.unwrap_or_default()
+}
+
+/// Prints the median time of one way to run some code, and its speedup
+/// over the baseline way, to compare them line by line.
+pub fn print_stats(name: &str, stats: Stats, baseline: Stats) {
+    let speedup = stats.speedup(&baseline);
+    println!("  {:<12} {:>10.2?} {:>6.1}x", name, stats.median, speedup);
+}
+
+/// Runs parse and part one and part two of a puzzle repeatedly, or only
+/// the given part.
+pub fn bench(
+    solution: &dyn Solution,
+    input: &str,
+    part: Option<u8>,
+    runs: Runs,
+) -> Result<Report> {
+    let parse = sample(runs, || solution.parse(input))?;
+    let parsed: Parsed = solution.parse(input)?;
+    let mut part_one = Stats::default();
+    let mut part_two = Stats::default();
+    if part != Some(2) {
+        part_one = sample(runs, || solution.part_one(&parsed))?;
+    }
+    if part != Some(1) {
+        part_two = sample(runs, || solution.part_two(&parsed))?;
+    }
+    Ok(Report {
+        day: solution.day(),
+        title: solution.title(),
+        parse,
+        part_one,
+        part_two,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_example;
+
+    #[test]
+    fn stats() {
+        let ms = Duration::from_millis;
+        let stats = Stats::from_samples(&[ms(4), ms(1), ms(7)]);
+        assert_eq!(stats.runs, 3);
+        assert_eq!(stats.min, ms(1));
+        assert_eq!(stats.median, ms(4));
+        assert_eq!(stats.mean, ms(4));
+        let stats = Stats::from_samples(&[ms(4), ms(1), ms(7), ms(2)]);
+        assert_eq!(stats.median, ms(3));
+        assert_eq!(Stats::from_samples(&[]).mean, Duration::ZERO);
+
+        let fast = Stats::from_samples(&[ms(2)]);
+        assert_eq!(fast.speedup(&stats), 1.5);
+        assert_eq!(Stats::default().speedup(&fast), 2_000_000.0);
+    }
+
+    #[test]
+    fn runs() {
+        let solution = crate::solution(1).unwrap();
+        let input = read_example(1);
+        let report = bench(solution, &input, None, Runs::exactly(5)).unwrap();
+        assert_eq!(report.day, 1);
+        assert_eq!(report.parse.runs, 5);
+        assert_eq!(report.part_two.runs, 5);
+        let report =
+            bench(solution, &input, Some(2), Runs::exactly(5)).unwrap();
+        assert_eq!(report.part_one.runs, 0);
+        assert_eq!(report.part_two.runs, 5);
+        assert!(bench(solution, "x", None, Runs::exactly(5)).is_err());
+        assert_eq!(time(Runs::exactly(4), || 1 + 1).runs, 4);
+    }
+}
@@ -1,15 +1,36 @@
-fn parse_input(input: &str) -> Vec<i32> {
-    return input.lines().map(|v| v.parse().unwrap()).collect();
+use crate::{Error, Result};
+
+const DAY: u8 = 1;
+
+solution!(DAY, "Sonar Sweep");
+
+#[derive(Debug)]
+pub struct Input {
+    pub depths: Vec<i32>,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let depths = input
+        .lines()
+        .enumerate()
+        .map(|(i, v)| {
+            v.parse()
+                .map_err(|_| Error::new(DAY, "invalid depth").at(i + 1, v))
+        })
+        .collect::<Result<_>>()?;
+    Ok(Input { depths })
 }
 
-pub fn part_one(input: &str) -> usize {
-    let input = parse_input(input);
-    return input.windows(2).filter(|&v| v[0] < v[1]).count();
+pub fn part_one(input: &Input) -> Result<usize> {
+    Ok(input.depths.windows(2).filter(|&v| v[0] < v[1]).count())
 }
 
-pub fn part_two(input: &str) -> usize {
-    let input = parse_input(input);
-    return input.windows(4).filter(|&v| v[0] < v[3]).count();
+pub fn part_two(input: &Input) -> Result<usize> {
+    Ok(input.depths.windows(4).filter(|&v| v[0] < v[3]).count())
+}
+
+pub fn solve(input: &Input) -> Result<(usize, usize)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -19,8 +40,16 @@ mod tests {
 
     #[test]
     fn example() {
-        let input = read_example(1);
-        assert_eq!(part_one(&input), 7);
-        assert_eq!(part_two(&input), 5);
+        let input = parse(&read_example(1)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 7);
+        assert_eq!(part_two(&input).unwrap(), 5);
+    }
+
+    #[test]
+    fn invalid_input() {
+        let err = parse("199\n2o0\n").unwrap_err();
+        assert_eq!(err.day, 1);
+        assert_eq!(err.line, Some(2));
+        assert_eq!(err.text.as_deref(), Some("2o0"));
     }
 }
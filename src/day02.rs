@@ -1,45 +1,71 @@
-fn parse_input(input: &str) -> Vec<(&str, i32)> {
-    input
+use crate::{Error, Result};
+
+const DAY: u8 = 2;
+
+solution!(DAY, "Dive!");
+
+#[derive(Debug, Copy, Clone)]
+pub enum Command {
+    Forward(i32),
+    Up(i32),
+    Down(i32),
+}
+
+pub struct Input {
+    pub commands: Vec<Command>,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let commands = input
         .lines()
-        .map(|v| {
-            let mut t = v.split(' ');
-            (t.next().unwrap(), t.next().unwrap().parse::<i32>().unwrap())
+        .enumerate()
+        .map(|(i, v)| {
+            let err = || Error::new(DAY, "invalid command").at(i + 1, v);
+            let (direction, x) = v.split_once(' ').ok_or_else(err)?;
+            let x = x.parse::<i32>().map_err(|_| err())?;
+            match direction {
+                "forward" => Ok(Command::Forward(x)),
+                "up" => Ok(Command::Up(x)),
+                "down" => Ok(Command::Down(x)),
+                _ => Err(err()),
+            }
         })
-        .collect()
+        .collect::<Result<_>>()?;
+    Ok(Input { commands })
 }
 
-pub fn part_one(input: &str) -> i32 {
-    let input = parse_input(input);
+pub fn part_one(input: &Input) -> Result<i32> {
     let mut pos = 0;
     let mut depth = 0;
-    for step in input {
+    for &step in &input.commands {
         match step {
-            ("forward", x) => pos += x,
-            ("up", x) => depth -= x,
-            ("down", x) => depth += x,
-            _ => {}
+            Command::Forward(x) => pos += x,
+            Command::Up(x) => depth -= x,
+            Command::Down(x) => depth += x,
         }
     }
-    pos * depth
+    Ok(pos * depth)
 }
 
-pub fn part_two(input: &str) -> i32 {
-    let input = parse_input(input);
+pub fn part_two(input: &Input) -> Result<i32> {
     let mut aim = 0;
     let mut pos = 0;
     let mut depth = 0;
-    for step in input {
+    for &step in &input.commands {
         match step {
-            ("forward", x) => {
+            Command::Forward(x) => {
                 pos += x;
                 depth += aim * x
             }
-            ("up", x) => aim -= x,
-            ("down", x) => aim += x,
-            _ => {}
+            Command::Up(x) => aim -= x,
+            Command::Down(x) => aim += x,
         }
     }
-    pos * depth
+    Ok(pos * depth)
+}
+
+pub fn solve(input: &Input) -> Result<(i32, i32)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -49,8 +75,8 @@ mod tests {
 
     #[test]
     fn example() {
-        let input = read_example(2);
-        assert_eq!(part_one(&input), 150);
-        assert_eq!(part_two(&input), 900);
+        let input = parse(&read_example(2)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 150);
+        assert_eq!(part_two(&input).unwrap(), 900);
     }
 }
@@ -1,8 +1,35 @@
-fn parse_input(input: &str) -> Vec<Vec<u8>> {
-    input
+use crate::{Error, Result};
+
+const DAY: u8 = 3;
+
+solution!(DAY, "Binary Diagnostic");
+
+pub struct Input {
+    pub report: Vec<Vec<u8>>,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let report: Vec<Vec<u8>> = input
         .lines()
-        .map(|x| x.bytes().map(|b| b - 48).collect())
-        .collect()
+        .enumerate()
+        .map(|(i, x)| {
+            x.bytes()
+                .map(|b| match b {
+                    b'0' | b'1' => Ok(b - 48),
+                    _ => Err(Error::new(DAY, "invalid bit").at(i + 1, x)),
+                })
+                .collect()
+        })
+        .collect::<Result<_>>()?;
+    let cols = match report.first() {
+        Some(row) if !row.is_empty() => row.len(),
+        _ => return Err(Error::new(DAY, "empty report")),
+    };
+    if let Some(i) = report.iter().position(|row| row.len() != cols) {
+        let line = input.lines().nth(i).unwrap_or_default();
+        return Err(Error::new(DAY, "inconsistent width").at(i + 1, line));
+    }
+    Ok(Input { report })
 }
 
 fn most_common_bit(bits: &[u8]) -> u8 {
@@ -23,8 +50,8 @@ fn bits_to_int(bits: &[u8]) -> i32 {
     bits.iter().fold(0, |acc, &x| acc << 1 | x as i32)
 }
 
-pub fn part_one(input: &str) -> i32 {
-    let input = parse_input(input);
+pub fn part_one(input: &Input) -> Result<i32> {
+    let input = &input.report;
     let cols = input[0].len();
     let mut gamma = vec![0u8; cols];
     let mut epsilon = vec![0u8; cols];
@@ -38,13 +65,12 @@ pub fn part_one(input: &str) -> i32 {
     let gamma_rate = bits_to_int(&gamma);
     let epsilon_rate = bits_to_int(&epsilon);
 
-    gamma_rate * epsilon_rate
+    Ok(gamma_rate * epsilon_rate)
 }
 
-pub fn part_two(input: &str) -> i32 {
-    let input = parse_input(input);
-
-    fn life_rating(input: &[Vec<u8>], is_oxygen: bool) -> i32 {
+pub fn part_two(input: &Input) -> Result<i32> {
+    let input = &input.report;
+    fn life_rating(input: &[Vec<u8>], is_oxygen: bool) -> Result<i32> {
         let mut grid = input.to_vec();
         for x in 0..input[0].len() {
             if grid.len() <= 1 {
@@ -57,13 +83,20 @@ pub fn part_two(input: &str) -> i32 {
             };
             grid.retain(|v| v[x] == mcb);
         }
-        bits_to_int(&grid[0])
+        match grid.first() {
+            Some(row) => Ok(bits_to_int(row)),
+            None => Err(Error::new(DAY, "no rating left")),
+        }
     }
 
-    let oxygen = life_rating(&input, true);
-    let co2 = life_rating(&input, false);
+    let oxygen = life_rating(input, true)?;
+    let co2 = life_rating(input, false)?;
+
+    Ok(oxygen * co2)
+}
 
-    oxygen * co2
+pub fn solve(input: &Input) -> Result<(i32, i32)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -81,13 +114,20 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(3);
-        assert_eq!(part_one(&input), 198);
+        let input = parse(&read_example(3)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 198);
     }
 
     #[test]
     fn example_two() {
-        let input = read_example(3);
-        assert_eq!(part_two(&input), 230);
+        let input = parse(&read_example(3)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 230);
+    }
+
+    #[test]
+    fn duplicate_rows() {
+        let input = parse("10\n10").unwrap();
+        let err = part_two(&input).unwrap_err();
+        assert_eq!(err.message, "no rating left");
     }
 }
@@ -1,40 +1,62 @@
 use std::collections::HashSet;
 
+use crate::{Error, Result};
+
+const DAY: u8 = 4;
+
+solution!(DAY, "Giant Squid");
 const WIDTH: usize = 5;
 
-fn parse_input(input: &str) -> (Vec<u8>, Vec<Vec<u8>>) {
-    let mut lines = input.lines();
-    let steps: Vec<u8> = lines
+pub struct Input {
+    pub numbers: Vec<u8>,
+    pub boards: Vec<Vec<u8>>,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let mut lines = input.lines().enumerate();
+    let (_, line) = lines
         .next()
-        .unwrap()
+        .ok_or_else(|| Error::new(DAY, "missing numbers"))?;
+    let steps: Vec<u8> = line
         .split(',')
-        .map(|v| v.parse().unwrap())
-        .collect();
+        .map(|v| v.parse())
+        .collect::<std::result::Result<_, _>>()
+        .map_err(|_| Error::new(DAY, "invalid numbers").at(1, line))?;
 
     let mut boards: Vec<Vec<u8>> = vec![];
     while lines.next().is_some() {
         let mut board: Vec<u8> = vec![];
         for _ in 0..WIDTH {
-            let mut row: Vec<u8> = lines
+            let (i, line) = lines
                 .next()
-                .unwrap()
+                .ok_or_else(|| Error::new(DAY, "incomplete board"))?;
+            let mut row: Vec<u8> = line
                 .split_whitespace()
-                .map(|v| v.parse().unwrap())
-                .collect();
+                .map(|v| v.parse())
+                .collect::<std::result::Result<_, _>>()
+                .map_err(|_| {
+                    Error::new(DAY, "invalid row").at(i + 1, line)
+                })?;
+            if row.len() != WIDTH {
+                return Err(Error::new(DAY, "invalid row").at(i + 1, line));
+            }
             board.append(&mut row);
         }
         boards.push(board)
     }
-    (steps, boards)
+    Ok(Input {
+        numbers: steps,
+        boards,
+    })
 }
 
-fn simulate(steps: Vec<u8>, boards: Vec<Vec<u8>>, win: usize) -> i32 {
+fn simulate(steps: &[u8], boards: &[Vec<u8>], win: usize) -> Result<i32> {
     let mut won: HashSet<usize> = HashSet::new();
     let mut marked: Vec<Vec<u8>> = vec![vec![]; boards.len()];
     let mut rows: Vec<Vec<u8>> = vec![vec![0; WIDTH]; boards.len()];
     let mut cols: Vec<Vec<u8>> = vec![vec![0; WIDTH]; boards.len()];
 
-    for step in steps {
+    for &step in steps {
         for (i, board) in boards.iter().enumerate() {
             if won.contains(&i) {
                 continue;
@@ -44,7 +66,7 @@ fn simulate(steps: Vec<u8>, boards: Vec<Vec<u8>>, win: usize) -> i32 {
                 continue;
             }
             let (j, &v) = found.unwrap();
-            marked[i].push(v as u8);
+            marked[i].push(v);
             rows[i][j / WIDTH] += 1;
             cols[i][j % WIDTH] += 1;
             if rows[i][j / WIDTH] < 5 && cols[i][j % WIDTH] < 5 {
@@ -56,22 +78,24 @@ fn simulate(steps: Vec<u8>, boards: Vec<Vec<u8>>, win: usize) -> i32 {
             }
             let t1: i32 = board.iter().map(|&v| v as i32).sum();
             let t2: i32 = marked[i].iter().map(|&v| v as i32).sum();
-            return (t1 - t2) * v as i32;
+            return Ok((t1 - t2) * v as i32);
         }
     }
 
-    0
+    Err(Error::new(DAY, "not enough boards win"))
+}
+
+pub fn part_one(input: &Input) -> Result<i32> {
+    simulate(&input.numbers, &input.boards, 1)
 }
 
-pub fn part_one(input: &str) -> i32 {
-    let (steps, boards) = parse_input(input);
-    simulate(steps, boards, 1)
+pub fn part_two(input: &Input) -> Result<i32> {
+    let win = input.boards.len();
+    simulate(&input.numbers, &input.boards, win)
 }
 
-pub fn part_two(input: &str) -> i32 {
-    let (steps, boards) = parse_input(input);
-    let win = boards.len();
-    simulate(steps, boards, win)
+pub fn solve(input: &Input) -> Result<(i32, i32)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -81,13 +105,13 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(4);
-        assert_eq!(part_one(&input), 4512);
+        let input = parse(&read_example(4)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 4512);
     }
 
     #[test]
     fn example_two() {
-        let input = read_example(4);
-        assert_eq!(part_two(&input), 1924);
+        let input = parse(&read_example(4)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 1924);
     }
 }
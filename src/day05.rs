@@ -1,15 +1,31 @@
 use std::collections::HashMap;
 
-fn parse_input(input: &str) -> Vec<(i32, i32, i32, i32)> {
-    input
+use crate::{Error, Result};
+
+const DAY: u8 = 5;
+
+solution!(DAY, "Hydrothermal Venture");
+
+pub struct Input {
+    pub lines: Vec<(i32, i32, i32, i32)>,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let lines = input
         .lines()
-        .map(|v| {
-            v.split(&[',', '-', '>', ' '][..])
+        .enumerate()
+        .map(|(i, line)| {
+            let v: Vec<i32> = line
+                .split(&[',', '-', '>', ' '][..])
                 .filter_map(|s| s.parse::<i32>().ok())
-                .collect()
+                .collect();
+            match v[..] {
+                [x1, y1, x2, y2] => Ok((x1, y1, x2, y2)),
+                _ => Err(Error::new(DAY, "invalid line").at(i + 1, line)),
+            }
         })
-        .map(|v: Vec<i32>| (v[0], v[1], v[2], v[3]))
-        .collect()
+        .collect::<Result<_>>()?;
+    Ok(Input { lines })
 }
 
 fn overlap_points(input: &[(i32, i32, i32, i32)], diagonal: bool) -> usize {
@@ -62,13 +78,15 @@ fn overlap_points(input: &[(i32, i32, i32, i32)], diagonal: bool) -> usize {
     points.values().filter(|&v| v > &1).count()
 }
 
-pub fn part_one(input: &str) -> usize {
-    let input = parse_input(input);
-    overlap_points(&input, false)
+pub fn part_one(input: &Input) -> Result<usize> {
+    Ok(overlap_points(&input.lines, false))
 }
-pub fn part_two(input: &str) -> usize {
-    let input = parse_input(input);
-    overlap_points(&input, true)
+pub fn part_two(input: &Input) -> Result<usize> {
+    Ok(overlap_points(&input.lines, true))
+}
+
+pub fn solve(input: &Input) -> Result<(usize, usize)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -78,13 +96,13 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(5);
-        assert_eq!(part_one(&input), 5);
+        let input = parse(&read_example(5)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 5);
     }
 
     #[test]
     fn example_two() {
-        let input = read_example(5);
-        assert_eq!(part_two(&input), 12);
+        let input = parse(&read_example(5)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 12);
     }
 }
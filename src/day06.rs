@@ -1,37 +1,65 @@
-fn parse_input(input: &str) -> Vec<u8> {
-    input
+use std::iter;
+
+use crate::{Error, Result};
+
+const DAY: u8 = 6;
+
+solution!(DAY, "Lanternfish");
+
+pub struct Input {
+    pub timers: Vec<u8>,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let timers = input
         .trim()
         .split(',')
-        .map(|v| v.parse().unwrap())
-        .collect()
+        .map(|v| match v.parse() {
+            Ok(timer) if timer <= 8 => Ok(timer),
+            _ => Err(Error::new(DAY, "invalid timer").at(1, v)),
+        })
+        .collect::<Result<_>>()?;
+    Ok(Input { timers })
 }
 
-fn spawn(input: &[u8], days: usize) -> u64 {
+/// Yields the number of lanternfish after 0, 1, 2, ... days.
+fn populations(input: &[u8]) -> impl Iterator<Item = u64> {
     let mut timers = [0_u64; 9];
     for &i in input {
         timers[i as usize] += 1;
     }
 
-    for _ in 0..days {
+    let days = iter::successors(Some(timers), |&timers| {
+        let mut timers = timers;
         let zero = timers[0];
         for i in 1..9 {
             timers[i - 1] = timers[i];
         }
         timers[6] += zero;
         timers[8] = zero;
-    }
+        Some(timers)
+    });
+
+    days.map(|timers| timers.iter().sum())
+}
+
+fn spawn(input: &[u8], days: usize) -> u64 {
+    populations(input).nth(days).unwrap_or_default()
+}
 
-    timers.iter().sum()
+pub fn part_one(input: &Input) -> Result<u64> {
+    Ok(spawn(&input.timers, 80))
 }
 
-pub fn part_one(input: &str) -> u64 {
-    let input = parse_input(input);
-    spawn(&input, 80)
+pub fn part_two(input: &Input) -> Result<u64> {
+    Ok(spawn(&input.timers, 256))
 }
 
-pub fn part_two(input: &str) -> u64 {
-    let input = parse_input(input);
-    spawn(&input, 256)
+pub fn solve(input: &Input) -> Result<(u64, u64)> {
+    let mut populations = populations(&input.timers);
+    let one = populations.nth(80).unwrap_or_default();
+    let two = populations.nth(256 - 80 - 1).unwrap_or_default();
+    Ok((one, two))
 }
 
 #[cfg(test)]
@@ -74,8 +102,9 @@ After 18 days: 6,0,6,4,5,6,0,1,1,2,6,0,1,1,1,2,2,3,3,4,6,7,8,8,8,8";
 
     #[test]
     fn example() {
-        let input = read_example(6);
-        assert_eq!(part_one(&input), 5934);
-        assert_eq!(part_two(&input), 26984457539);
+        let input = parse(&read_example(6)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 5934);
+        assert_eq!(part_two(&input).unwrap(), 26984457539);
+        assert_eq!(solve(&input).unwrap(), (5934, 26984457539));
     }
 }
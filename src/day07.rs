@@ -1,17 +1,31 @@
-fn parse_input(input: &str) -> Vec<i32> {
-    input
+use crate::{Error, Result};
+
+const DAY: u8 = 7;
+
+solution!(DAY, "The Treachery of Whales");
+
+pub struct Input {
+    pub positions: Vec<i32>,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let positions = input
         .trim()
         .split(',')
-        .map(|s| s.parse().unwrap())
-        .collect()
+        .map(|s| {
+            s.parse()
+                .map_err(|_| Error::new(DAY, "invalid position").at(1, s))
+        })
+        .collect::<Result<_>>()?;
+    Ok(Input { positions })
 }
 
 fn find_cheapest<F>(input: &[i32], f: F) -> i32
 where
     F: Fn(i32, i32) -> i32,
 {
-    let &max = input.iter().max().unwrap();
-    let &min = input.iter().min().unwrap();
+    let max = input.iter().copied().max().unwrap_or_default();
+    let min = input.iter().copied().min().unwrap_or_default();
     let mut cheapest = i32::MAX;
     for i in min..=max {
         let cost: i32 = input.iter().map(|&v| f(i, v)).sum();
@@ -22,17 +36,19 @@ where
     cheapest
 }
 
-pub fn part_one(input: &str) -> i32 {
-    let input = parse_input(input);
-    find_cheapest(&input, |a, b| (a - b).abs())
+pub fn part_one(input: &Input) -> Result<i32> {
+    Ok(find_cheapest(&input.positions, |a, b| (a - b).abs()))
 }
 
-pub fn part_two(input: &str) -> i32 {
-    let input = parse_input(input);
-    find_cheapest(&input, |a, b| {
+pub fn part_two(input: &Input) -> Result<i32> {
+    Ok(find_cheapest(&input.positions, |a, b| {
         let n = (a - b).abs();
         n * (n + 1) / 2
-    })
+    }))
+}
+
+pub fn solve(input: &Input) -> Result<(i32, i32)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -42,8 +58,8 @@ mod tests {
 
     #[test]
     fn example() {
-        let input = read_example(7);
-        assert_eq!(part_one(&input), 37);
-        assert_eq!(part_two(&input), 168);
+        let input = parse(&read_example(7)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 37);
+        assert_eq!(part_two(&input).unwrap(), 168);
     }
 }
@@ -1,15 +1,43 @@
 use std::collections::{HashMap, HashSet};
 
-fn parse_input(input: &str) -> Vec<Vec<&str>> {
-    input
+use crate::{Error, Result};
+
+const DAY: u8 = 8;
+
+solution!(DAY, "Seven Segment Search");
+
+pub struct Input {
+    pub entries: Vec<Vec<String>>,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let entries = input
         .lines()
-        .map(|s| s.split_whitespace().collect())
-        .collect()
+        .enumerate()
+        .map(|(i, s)| {
+            let entry: Vec<String> =
+                s.split_whitespace().map(String::from).collect();
+            let is_valid = entry.len() == 15
+                && entry[10] == "|"
+                && entry.iter().enumerate().all(|(j, code)| {
+                    j == 10
+                        || (!code.is_empty()
+                            && code
+                                .bytes()
+                                .all(|b| (b'a'..=b'g').contains(&b)))
+                });
+            match is_valid {
+                true => Ok(entry),
+                false => Err(Error::new(DAY, "invalid entry").at(i + 1, s)),
+            }
+        })
+        .collect::<Result<_>>()?;
+    Ok(Input { entries })
 }
 
-pub fn part_one(input: &str) -> usize {
-    let input = parse_input(input);
-    input
+pub fn part_one(input: &Input) -> Result<usize> {
+    Ok(input
+        .entries
         .iter()
         .map(|line| {
             line[11..]
@@ -17,10 +45,10 @@ pub fn part_one(input: &str) -> usize {
                 .filter(|code| matches!(code.len(), 2 | 4 | 3 | 7))
                 .count()
         })
-        .sum()
+        .sum())
 }
 
-fn build_code_map<'a>(codes: &'a [&str]) -> HashMap<&'a str, u8> {
+fn build_code_map<'a>(codes: &'a [&str]) -> Option<HashMap<&'a str, u8>> {
     let mut code_map: HashMap<&str, u8> = HashMap::new();
     let mut number_sets: HashMap<u8, HashSet<char>> = HashMap::new();
 
@@ -44,9 +72,9 @@ fn build_code_map<'a>(codes: &'a [&str]) -> HashMap<&'a str, u8> {
 
     for code in codes.iter().filter(|code| code.len() == 6) {
         let sc: HashSet<char> = HashSet::from_iter(code.chars());
-        if number_sets.get(&4).unwrap().is_subset(&sc) {
+        if number_sets.get(&4)?.is_subset(&sc) {
             set_code!(code, 9);
-        } else if number_sets.get(&7).unwrap().is_subset(&sc) {
+        } else if number_sets.get(&7)?.is_subset(&sc) {
             set_code!(code, 0);
         } else {
             set_code!(code, 6);
@@ -55,22 +83,21 @@ fn build_code_map<'a>(codes: &'a [&str]) -> HashMap<&'a str, u8> {
 
     for code in codes.iter().filter(|v| v.len() == 5) {
         let sc: HashSet<char> = HashSet::from_iter(code.chars());
-        if number_sets.get(&7).unwrap().is_subset(&sc) {
+        if number_sets.get(&7)?.is_subset(&sc) {
             set_code!(code, 3);
-        } else if number_sets.get(&6).unwrap().intersection(&sc).count() == 4
-        {
+        } else if number_sets.get(&6)?.intersection(&sc).count() == 4 {
             set_code!(code, 2);
         } else {
             set_code!(code, 5);
         }
     }
 
-    code_map
+    Some(code_map)
 }
 
-pub fn part_two(input: &str) -> i32 {
-    let input = parse_input(input);
+pub fn part_two(input: &Input) -> Result<i32> {
     let input: Vec<Vec<String>> = input
+        .entries
         .iter()
         .map(|line| {
             line.iter()
@@ -86,21 +113,32 @@ pub fn part_two(input: &str) -> i32 {
 
     input
         .iter()
-        .map(|line| {
+        .enumerate()
+        .map(|(i, line)| {
             // build code_map by the left ten codes
             let lhs: Vec<&str> =
                 line[..10].iter().map(|code| code.as_str()).collect();
-            let code_map = build_code_map(&lhs);
             // decode the right four codes
             let rhs: Vec<&str> =
                 line[11..].iter().map(|code| code.as_str()).collect();
-            rhs.iter()
-                .map(|code| *(code_map.get(code).unwrap()) as i32)
-                .fold(0, |acc, x| acc * 10 + x)
+            build_code_map(&lhs)
+                .and_then(|code_map| {
+                    rhs.iter().try_fold(0, |acc, code| {
+                        Some(acc * 10 + *code_map.get(code)? as i32)
+                    })
+                })
+                .ok_or_else(|| {
+                    let entry = line.join(" ");
+                    Error::new(DAY, "cannot decode entry").at(i + 1, &entry)
+                })
         })
         .sum()
 }
 
+pub fn solve(input: &Input) -> Result<(usize, i32)> {
+    Ok((part_one(input)?, part_two(input)?))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -108,8 +146,8 @@ mod tests {
 
     #[test]
     fn example() {
-        let input = read_example(8);
-        assert_eq!(part_one(&input), 26);
-        assert_eq!(part_two(&input), 61229);
+        let input = parse(&read_example(8)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 26);
+        assert_eq!(part_two(&input).unwrap(), 61229);
     }
 }
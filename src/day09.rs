@@ -1,10 +1,22 @@
 use std::collections::HashSet;
 
-fn parse_input(input: &str) -> Vec<Vec<i32>> {
-    input
-        .lines()
-        .map(|s| s.bytes().map(|v| (v - 48) as i32).collect())
-        .collect()
+use crate::{parse_digits, Error, Result};
+
+const DAY: u8 = 9;
+
+solution!(DAY, "Smoke Basin");
+
+pub struct Input {
+    pub heightmap: Vec<Vec<i32>>,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let grid = parse_digits(DAY, input)?;
+    let heightmap = grid
+        .into_iter()
+        .map(|row| row.into_iter().map(i32::from).collect())
+        .collect();
+    Ok(Input { heightmap })
 }
 
 const NEIGHBORS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
@@ -38,18 +50,18 @@ fn low_points(grid: &[&[i32]]) -> Vec<(usize, usize)> {
     points
 }
 
-pub fn part_one(input: &str) -> i32 {
-    let grid = parse_input(input);
-    let grid: Vec<&[i32]> = grid.iter().map(|row| row.as_slice()).collect();
-    low_points(&grid)
+pub fn part_one(input: &Input) -> Result<i32> {
+    let grid: Vec<&[i32]> =
+        input.heightmap.iter().map(|row| row.as_slice()).collect();
+    Ok(low_points(&grid)
         .iter()
         .map(|(x, y)| grid[*y][*x] + 1)
-        .sum()
+        .sum())
 }
 
-pub fn part_two(input: &str) -> i32 {
-    let grid = parse_input(input);
-    let grid: Vec<&[i32]> = grid.iter().map(|row| row.as_slice()).collect();
+pub fn part_two(input: &Input) -> Result<i32> {
+    let grid: Vec<&[i32]> =
+        input.heightmap.iter().map(|row| row.as_slice()).collect();
     let height = grid.len();
     let width = grid[0].len();
     let points = low_points(&grid);
@@ -74,9 +86,16 @@ pub fn part_two(input: &str) -> i32 {
         }
         basins.push(visited.len() as i32);
     }
+    if basins.len() < 3 {
+        return Err(Error::new(DAY, "fewer than three basins"));
+    }
     basins.sort_unstable();
     basins.reverse();
-    basins[0] * basins[1] * basins[2]
+    Ok(basins[0] * basins[1] * basins[2])
+}
+
+pub fn solve(input: &Input) -> Result<(i32, i32)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -86,13 +105,13 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(9);
-        assert_eq!(part_one(&input), 15);
+        let input = parse(&read_example(9)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 15);
     }
 
     #[test]
     fn example_two() {
-        let input = read_example(9);
-        assert_eq!(part_two(&input), 1134);
+        let input = parse(&read_example(9)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 1134);
     }
 }
@@ -1,15 +1,39 @@
-fn parse_input(input: &str) -> Vec<Vec<char>> {
-    input.lines().map(|line| line.chars().collect()).collect()
+use crate::{Error, Result};
+
+const DAY: u8 = 10;
+
+solution!(DAY, "Syntax Scoring");
+
+pub struct Input {
+    pub lines: Vec<Vec<char>>,
 }
 
-pub fn part_one(input: &str) -> i32 {
-    let input = parse_input(input);
+pub fn parse(input: &str) -> Result<Input> {
+    let lines = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.chars()
+                .map(|ch| match ch {
+                    '(' | '[' | '{' | '<' | ')' | ']' | '}' | '>' => Ok(ch),
+                    _ => {
+                        Err(Error::new(DAY, "invalid character")
+                            .at(i + 1, line))
+                    }
+                })
+                .collect()
+        })
+        .collect::<Result<_>>()?;
+    Ok(Input { lines })
+}
+
+pub fn part_one(input: &Input) -> Result<i32> {
     let mut score = 0;
-    for line in &input {
+    for line in &input.lines {
         let mut stack: Vec<char> = Vec::new();
         macro_rules! check_corruption {
             ($a:expr, $p:expr) => {
-                if stack.pop().unwrap() != $a {
+                if stack.pop() != Some($a) {
                     score += $p;
                     break;
                 }
@@ -26,19 +50,18 @@ pub fn part_one(input: &str) -> i32 {
             };
         }
     }
-    score
+    Ok(score)
 }
 
-pub fn part_two(input: &str) -> i64 {
-    let input = parse_input(input);
+pub fn part_two(input: &Input) -> Result<i64> {
     let mut scores: Vec<i64> = Vec::new();
-    for line in &input {
+    for line in &input.lines {
         let mut score = 0;
         let mut stack: Vec<char> = Vec::new();
         let mut corrupted = false;
         macro_rules! check_corruption {
             ($a:expr) => {
-                if stack.pop().unwrap() != $a {
+                if stack.pop() != Some($a) {
                     corrupted = true;
                     break;
                 }
@@ -70,8 +93,14 @@ pub fn part_two(input: &str) -> i64 {
         scores.push(score);
     }
     scores.sort_unstable();
-    let score = scores.get(scores.len() / 2).unwrap();
-    *score
+    scores
+        .get(scores.len() / 2)
+        .copied()
+        .ok_or_else(|| Error::new(DAY, "no incomplete lines"))
+}
+
+pub fn solve(input: &Input) -> Result<(i32, i64)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -81,13 +110,13 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(10);
-        assert_eq!(part_one(&input), 26397);
+        let input = parse(&read_example(10)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 26397);
     }
 
     #[test]
     fn example_two() {
-        let input = read_example(10);
-        assert_eq!(part_two(&input), 288957);
+        let input = parse(&read_example(10)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 288957);
     }
 }
@@ -1,8 +1,16 @@
-fn parse_input(input: &str) -> Vec<Vec<u8>> {
-    input
-        .lines()
-        .map(|s| s.bytes().map(|b| b - 48).collect())
-        .collect()
+use crate::{parse_digits, Result};
+
+const DAY: u8 = 11;
+
+solution!(DAY, "Dumbo Octopus");
+
+pub struct Input {
+    pub energy: Vec<Vec<u8>>,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let energy = parse_digits(DAY, input)?;
+    Ok(Input { energy })
 }
 
 const NEIGHBORS: [(i32, i32); 8] = [
@@ -16,7 +24,7 @@ const NEIGHBORS: [(i32, i32); 8] = [
     (1, 1),
 ];
 
-fn flash(grid: &mut Vec<Vec<u8>>) -> usize {
+fn flash(grid: &mut [Vec<u8>]) -> usize {
     let height = grid.len();
     let width = grid[0].len();
 
@@ -57,24 +65,43 @@ fn flash(grid: &mut Vec<Vec<u8>>) -> usize {
     count
 }
 
-pub fn part_one(input: &str) -> usize {
-    let mut grid = parse_input(input);
+pub fn part_one(input: &Input) -> Result<usize> {
+    let mut grid = input.energy.clone();
     let mut count = 0;
     for _ in 0..100 {
         count += flash(&mut grid);
     }
-    count
+    Ok(count)
 }
 
-pub fn part_two(input: &str) -> usize {
-    let mut grid = parse_input(input);
+pub fn part_two(input: &Input) -> Result<usize> {
+    let mut grid = input.energy.clone();
     let height = grid.len();
     let width = grid[0].len();
     let mut step = 1;
     while flash(&mut grid) < width * height {
         step += 1;
     }
-    step
+    Ok(step)
+}
+
+pub fn solve(input: &Input) -> Result<(usize, usize)> {
+    let mut grid = input.energy.clone();
+    let size = grid.len() * grid[0].len();
+    let mut count = 0;
+    let mut synchronized = None;
+    let mut step = 0;
+    while step < 100 || synchronized.is_none() {
+        step += 1;
+        let flashes = flash(&mut grid);
+        if step <= 100 {
+            count += flashes;
+        }
+        if flashes == size && synchronized.is_none() {
+            synchronized = Some(step);
+        }
+    }
+    Ok((count, synchronized.unwrap_or_default()))
 }
 
 #[cfg(test)]
@@ -84,13 +111,14 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(11);
-        assert_eq!(part_one(&input), 1656);
+        let input = parse(&read_example(11)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 1656);
     }
 
     #[test]
     fn example_two() {
-        let input = read_example(11);
-        assert_eq!(part_two(&input), 195);
+        let input = parse(&read_example(11)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 195);
+        assert_eq!(solve(&input).unwrap(), (1656, 195));
     }
 }
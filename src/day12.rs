@@ -1,53 +1,81 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-fn parse_input(input: &str) -> HashMap<&str, Vec<&str>> {
+use crate::{Error, Result};
+
+const DAY: u8 = 12;
+
+solution!(DAY, "Passage Pathing");
+
+fn is_big(cave: &str) -> bool {
+    cave.chars().all(|c| c.is_ascii_uppercase())
+}
+
+pub struct Input {
+    pub caves: HashMap<String, Vec<String>>,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
     let input: Vec<(&str, &str)> = input
         .lines()
-        .map(|line| line.split('-').collect())
-        .map(|nodes: Vec<&str>| (nodes[0], nodes[1]))
-        .collect();
+        .enumerate()
+        .map(|(i, line)| match line.split_once('-') {
+            Some((a, b)) if !a.is_empty() && !b.is_empty() => {
+                if is_big(a) && is_big(b) {
+                    // two connected big caves could be visited forever
+                    let err = Error::new(DAY, "connected big caves");
+                    return Err(err.at(i + 1, line));
+                }
+                Ok((a, b))
+            }
+            _ => Err(Error::new(DAY, "invalid connection").at(i + 1, line)),
+        })
+        .collect::<Result<_>>()?;
 
-    let mut map: HashMap<&str, Vec<&str>> = HashMap::new();
+    let mut caves: HashMap<String, Vec<String>> = HashMap::new();
+    let mut connect = |a: &str, b: &str| {
+        caves.entry(a.to_string()).or_default().push(b.to_string());
+    };
     for (a, b) in input {
         match (a, b) {
-            ("start", _) | (_, "end") => {
-                map.entry(a).or_default().push(b);
-            }
-            ("end", _) | (_, "start") => {
-                map.entry(b).or_default().push(a);
-            }
+            ("start", _) | (_, "end") => connect(a, b),
+            ("end", _) | (_, "start") => connect(b, a),
             _ => {
-                map.entry(a).or_default().push(b);
-                map.entry(b).or_default().push(a);
+                connect(a, b);
+                connect(b, a);
             }
         }
     }
-    map
+    Ok(Input { caves })
 }
 
-fn search(map: &HashMap<&str, Vec<&str>>, enable_twice: bool) -> usize {
+fn search(map: &HashMap<String, Vec<String>>, enable_twice: bool) -> usize {
     struct Env<'a> {
-        map: &'a HashMap<&'a str, Vec<&'a str>>,
+        map: &'a HashMap<String, Vec<String>>,
         visited: HashSet<&'a str>,
         enable_twice: bool,
         twiced: Option<&'a str>,
         count: usize,
     }
 
-    fn dfs(mut env: &mut Env, node: &str) {
+    fn dfs<'a>(env: &mut Env<'a>, node: &str) {
         if node == "end" {
             env.count += 1;
             return;
         }
-        for next in env.map.get(node).unwrap() {
+        let map = env.map;
+        let nexts = match map.get(node) {
+            Some(nexts) => nexts,
+            None => return,
+        };
+        for next in nexts.iter().map(String::as_str) {
             if env.visited.contains(next) {
                 if !env.enable_twice || env.twiced.is_some() {
                     continue;
                 }
                 env.twiced = Some(next);
             }
-            if next.chars().next().unwrap().is_lowercase() {
+            if !is_big(next) {
                 env.visited.insert(next);
             }
             dfs(env, next);
@@ -74,64 +102,42 @@ fn search(map: &HashMap<&str, Vec<&str>>, enable_twice: bool) -> usize {
     env.count
 }
 
-pub fn part_one(input: &str) -> usize {
-    let map = parse_input(input);
-    search(&map, false)
+pub fn part_one(input: &Input) -> Result<usize> {
+    Ok(search(&input.caves, false))
+}
+
+pub fn part_two(input: &Input) -> Result<usize> {
+    Ok(search(&input.caves, true))
 }
 
-pub fn part_two(input: &str) -> usize {
-    let map = parse_input(input);
-    search(&map, true)
+pub fn solve(input: &Input) -> Result<(usize, usize)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::read_example;
-
-    const LARGER: &str = "dc-end
-HN-start
-start-kj
-dc-start
-dc-HN
-LN-dc
-HN-end
-kj-sa
-kj-HN
-kj-dc";
-
-    const EVER_LARGER: &str = "fs-end
-he-DX
-fs-he
-start-DX
-pj-DX
-end-zg
-zg-sl
-zg-pj
-pj-he
-RW-he
-fs-DX
-pj-RW
-zg-RW
-start-pj
-he-WI
-zg-he
-pj-fs
-start-RW";
+    use crate::{read_as_string, read_example};
 
     #[test]
     fn example_one() {
-        let input = read_example(12);
-        assert_eq!(part_one(&input), 10);
-        assert_eq!(part_one(LARGER), 19);
-        assert_eq!(part_one(EVER_LARGER), 226);
+        let input = parse(&read_example(12)).unwrap();
+        let larger = parse(&read_as_string(12, "example2").unwrap()).unwrap();
+        let largest =
+            parse(&read_as_string(12, "example3").unwrap()).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 10);
+        assert_eq!(part_one(&larger).unwrap(), 19);
+        assert_eq!(part_one(&largest).unwrap(), 226);
     }
 
     #[test]
     fn example_two() {
-        let input = read_example(12);
-        assert_eq!(part_two(&input), 36);
-        assert_eq!(part_two(LARGER), 103);
-        assert_eq!(part_two(EVER_LARGER), 3509);
+        let input = parse(&read_example(12)).unwrap();
+        let larger = parse(&read_as_string(12, "example2").unwrap()).unwrap();
+        let largest =
+            parse(&read_as_string(12, "example3").unwrap()).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 36);
+        assert_eq!(part_two(&larger).unwrap(), 103);
+        assert_eq!(part_two(&largest).unwrap(), 3509);
     }
 }
@@ -1,33 +1,54 @@
 use std::collections::HashSet;
 
-type Point = (i32, i32);
+use crate::{Error, Result};
 
-fn parse_input(input: &str) -> (HashSet<Point>, Vec<(char, i32)>) {
+const DAY: u8 = 13;
+
+solution!(DAY, "Transparent Origami");
+
+pub type Point = (i32, i32);
+pub type Fold = (char, i32);
+
+pub struct Input {
+    pub dots: HashSet<Point>,
+    pub folds: Vec<Fold>,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
     let mut points: HashSet<Point> = HashSet::new();
-    let mut folds: Vec<(char, i32)> = Vec::new();
-    let mut lines = input.lines();
-    for line in lines.by_ref() {
+    let mut folds: Vec<Fold> = Vec::new();
+    let mut lines = input.lines().enumerate();
+    for (i, line) in lines.by_ref() {
         if line.trim().is_empty() {
             break;
         }
-        let p: Vec<i32> =
-            line.split(',').map(|v| v.parse().unwrap()).collect();
-        let p = (p[0], p[1]);
+        let p = line
+            .split_once(',')
+            .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)))
+            .ok_or_else(|| Error::new(DAY, "invalid dot").at(i + 1, line))?;
         points.insert(p);
     }
-    for line in lines.by_ref() {
-        let f = &line["fold along ".len()..].split_once('=').unwrap();
-        let f: (char, i32) =
-            (f.0.chars().next().unwrap(), f.1.parse().unwrap());
+    for (i, line) in lines.by_ref() {
+        let f = line
+            .strip_prefix("fold along ")
+            .and_then(|f| f.split_once('='))
+            .and_then(|f| match f.0 {
+                "x" | "y" => Some((f.0.chars().next()?, f.1.parse().ok()?)),
+                _ => None,
+            })
+            .ok_or_else(|| Error::new(DAY, "invalid fold").at(i + 1, line))?;
         folds.push(f);
     }
-    (points, folds)
+    Ok(Input {
+        dots: points,
+        folds,
+    })
 }
 
 fn fold(
     points: &HashSet<(i32, i32)>,
     at: (char, i32),
-) -> HashSet<(i32, i32)> {
+) -> Result<HashSet<(i32, i32)>> {
     let mut new_points: HashSet<(i32, i32)> = HashSet::new();
     for &(x, y) in points.iter() {
         match at {
@@ -44,16 +65,17 @@ fn fold(
                 new_points.insert((x, z + z - y));
             }
             _ => {
-                unreachable!()
+                let msg = format!("dot ({},{}) lies on the fold line", x, y);
+                return Err(Error::new(DAY, msg));
             }
         }
     }
-    new_points
+    Ok(new_points)
 }
 
 fn points_as_string(points: &HashSet<(i32, i32)>) -> String {
-    let w = points.iter().map(|(x, _)| x).max().unwrap() + 1;
-    let h = points.iter().map(|(_, y)| y).max().unwrap() + 1;
+    let w = points.iter().map(|&(x, _)| x + 1).max().unwrap_or_default();
+    let h = points.iter().map(|&(_, y)| y + 1).max().unwrap_or_default();
     let mut s = String::with_capacity((w * h) as usize);
     s.push('\n');
     for y in 0..h {
@@ -68,18 +90,33 @@ fn points_as_string(points: &HashSet<(i32, i32)>) -> String {
     s
 }
 
-pub fn part_one(input: &str) -> usize {
-    let (mut points, folds) = parse_input(input);
-    points = fold(&points, folds[0]);
-    points.len()
+pub fn part_one(input: &Input) -> Result<usize> {
+    let &at = input
+        .folds
+        .first()
+        .ok_or_else(|| Error::new(DAY, "missing fold instructions"))?;
+    Ok(fold(&input.dots, at)?.len())
+}
+
+pub fn part_two(input: &Input) -> Result<String> {
+    let mut points = input.dots.clone();
+    for &at in &input.folds {
+        points = fold(&points, at)?;
+    }
+    Ok(points_as_string(&points))
 }
 
-pub fn part_two(input: &str) -> String {
-    let (mut points, folds) = parse_input(input);
-    for at in folds {
-        points = fold(&points, at);
+pub fn solve(input: &Input) -> Result<(usize, String)> {
+    let (&at, folds) = input
+        .folds
+        .split_first()
+        .ok_or_else(|| Error::new(DAY, "missing fold instructions"))?;
+    let mut points = fold(&input.dots, at)?;
+    let count = points.len();
+    for &at in folds {
+        points = fold(&points, at)?;
     }
-    points_as_string(&points)
+    Ok((count, points_as_string(&points)))
 }
 
 #[cfg(test)]
@@ -89,7 +126,7 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(13);
-        assert_eq!(part_one(&input), 17);
+        let input = parse(&read_example(13)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 17);
     }
 }
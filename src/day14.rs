@@ -1,21 +1,41 @@
 use std::collections::HashMap;
 
-type Pair = (char, char);
-type Rules = HashMap<Pair, char>;
+use crate::{Error, Result};
+
+const DAY: u8 = 14;
+
+solution!(DAY, "Extended Polymerization");
+
+pub type Pair = (char, char);
+pub type Rules = HashMap<Pair, char>;
 type Elements = HashMap<char, i64>;
 type Cache = HashMap<(Pair, i32), Elements>;
 
-fn parse_input(input: &str) -> (&str, Rules) {
-    let mut lines = input.lines();
-    let template = lines.next().unwrap();
+pub struct Input {
+    pub template: String,
+    pub rules: Rules,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let mut lines = input.lines().enumerate();
+    let template = match lines.next() {
+        Some((_, template)) if !template.is_empty() => template,
+        _ => return Err(Error::new(DAY, "missing polymer template")),
+    };
     lines.next();
     let mut rules: HashMap<Pair, char> = HashMap::new();
-    for line in lines.by_ref() {
-        let mut chars = line.chars();
-        let pair = (chars.next().unwrap(), chars.next().unwrap());
-        rules.insert(pair, chars.next_back().unwrap());
+    for (i, line) in lines.by_ref() {
+        let chars: Vec<char> = line.chars().collect();
+        match chars[..] {
+            [a, b, ' ', '-', '>', ' ', c] => rules.insert((a, b), c),
+            _ => {
+                let err = Error::new(DAY, "invalid insertion rule");
+                return Err(err.at(i + 1, line));
+            }
+        };
     }
-    (template, rules)
+    let template = template.to_string();
+    Ok(Input { template, rules })
 }
 
 fn merge(to: &mut Elements, from: &Elements) {
@@ -66,7 +86,10 @@ fn grow(template: &str, rules: &Rules, depth: i32) -> i64 {
     let mut elements: Elements = HashMap::new();
 
     let mut chars = template.chars();
-    let mut a = chars.next().unwrap();
+    let mut a = match chars.next() {
+        Some(a) => a,
+        None => return 0,
+    };
     for b in chars.by_ref() {
         let subelements = grow_pair(&mut cache, rules, (a, b), depth);
         merge(&mut elements, &subelements);
@@ -86,12 +109,14 @@ fn grow(template: &str, rules: &Rules, depth: i32) -> i64 {
     max - min
 }
 
-pub fn part_one(input: &str) -> i64 {
-    let (template, rules) = parse_input(input);
-    grow(template, &rules, 10)
+pub fn part_one(input: &Input) -> Result<i64> {
+    Ok(grow(&input.template, &input.rules, 10))
+}
+
+pub fn part_two(input: &Input) -> Result<i64> {
+    Ok(grow(&input.template, &input.rules, 40))
 }
 
-pub fn part_two(input: &str) -> i64 {
-    let (template, rules) = parse_input(input);
-    grow(template, &rules, 40)
+pub fn solve(input: &Input) -> Result<(i64, i64)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
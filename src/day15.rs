@@ -2,11 +2,19 @@ use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 use std::collections::HashSet;
 
-fn parse_input(input: &str) -> Vec<Vec<u8>> {
-    input
-        .lines()
-        .map(|s| s.bytes().map(|b| b - 48).collect())
-        .collect()
+use crate::{parse_digits, Error, Result};
+
+const DAY: u8 = 15;
+
+solution!(DAY, "Chiton");
+
+pub struct Input {
+    pub risk: Vec<Vec<u8>>,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let risk = parse_digits(DAY, input)?;
+    Ok(Input { risk })
 }
 
 const NEIGHBORS: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
@@ -82,14 +90,18 @@ fn shortest_path(grid: &[Vec<u8>], times: usize) -> Option<usize> {
     None
 }
 
-pub fn part_one(input: &str) -> usize {
-    let grid = parse_input(input);
-    shortest_path(&grid, 1).unwrap()
+pub fn part_one(input: &Input) -> Result<usize> {
+    shortest_path(&input.risk, 1)
+        .ok_or_else(|| Error::new(DAY, "no path found"))
+}
+
+pub fn part_two(input: &Input) -> Result<usize> {
+    shortest_path(&input.risk, 5)
+        .ok_or_else(|| Error::new(DAY, "no path found"))
 }
 
-pub fn part_two(input: &str) -> usize {
-    let grid = parse_input(input);
-    shortest_path(&grid, 5).unwrap()
+pub fn solve(input: &Input) -> Result<(usize, usize)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -99,13 +111,13 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(15);
-        assert_eq!(part_one(&input), 40);
+        let input = parse(&read_example(15)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 40);
     }
 
     #[test]
     fn example_two() {
-        let input = read_example(15);
-        assert_eq!(part_two(&input), 315);
+        let input = parse(&read_example(15)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 315);
     }
 }
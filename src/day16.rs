@@ -1,139 +1,576 @@
-fn parse_input(input: &str) -> String {
-    input
-        .trim()
-        .chars()
-        .map(|c| match c {
-            '0' => "0000",
-            '1' => "0001",
-            '2' => "0010",
-            '3' => "0011",
-            '4' => "0100",
-            '5' => "0101",
-            '6' => "0110",
-            '7' => "0111",
-            '8' => "1000",
-            '9' => "1001",
-            'A' => "1010",
-            'B' => "1011",
-            'C' => "1100",
-            'D' => "1101",
-            'E' => "1110",
-            'F' => "1111",
-            _ => unreachable!(),
-        })
-        .map(|s| s.to_string())
-        .collect::<Vec<String>>()
-        .join("")
-}
+pub mod bignum;
+pub mod bits;
+mod eval;
+mod print;
+
+use std::fmt;
+
+use crate::{Error, Result};
+use bits::{BitReader, BitWriter};
+pub use eval::EvalError;
+pub use print::tree;
+
+const DAY: u8 = 16;
+
+solution!(DAY, "Packet Decoder");
 
 #[derive(Debug)]
-#[allow(dead_code)]
-struct Packet {
-    version: u8,
-    type_id: u8,
-    value: u64,
-    children: Vec<Packet>,
+pub struct Input {
+    pub packet: Packet,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let packet = Packet::from_hex_str(input)?;
+    Ok(Input { packet })
+}
+
+/// The operation of an operator packet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    Sum,
+    Product,
+    Minimum,
+    Maximum,
+    GreaterThan,
+    LessThan,
+    EqualTo,
+}
+
+impl Op {
+    pub fn from_type_id(type_id: u8) -> Option<Op> {
+        match type_id {
+            0 => Some(Op::Sum),
+            1 => Some(Op::Product),
+            2 => Some(Op::Minimum),
+            3 => Some(Op::Maximum),
+            5 => Some(Op::GreaterThan),
+            6 => Some(Op::LessThan),
+            7 => Some(Op::EqualTo),
+            _ => None,
+        }
+    }
+
+    pub fn type_id(self) -> u8 {
+        match self {
+            Op::Sum => 0,
+            Op::Product => 1,
+            Op::Minimum => 2,
+            Op::Maximum => 3,
+            Op::GreaterThan => 5,
+            Op::LessThan => 6,
+            Op::EqualTo => 7,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Op::Sum => "sum",
+            Op::Product => "product",
+            Op::Minimum => "minimum",
+            Op::Maximum => "maximum",
+            Op::GreaterThan => "greater than",
+            Op::LessThan => "less than",
+            Op::EqualTo => "equal to",
+        }
+    }
+
+    /// The operator of the operation in an S-expression.
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Op::Sum => "+",
+            Op::Product => "*",
+            Op::Minimum => "min",
+            Op::Maximum => "max",
+            Op::GreaterThan => ">",
+            Op::LessThan => "<",
+            Op::EqualTo => "=",
+        }
+    }
+
+    /// Whether the operation compares exactly two sub-packets.
+    pub fn is_comparison(self) -> bool {
+        matches!(self, Op::GreaterThan | Op::LessThan | Op::EqualTo)
+    }
+}
+
+/// The type id of literal value packets.
+const LITERAL: u8 = 4;
+
+/// The fewest bits a packet takes: a header and a single group.
+const MIN_PACKET_BITS: usize = 11;
+
+/// Why a transmission could not be decoded, and where.
+///
+/// The length type of an operator is a single bit, so every length type
+/// is known.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// A character other than an uppercase hexadecimal digit, counting
+    /// columns from 1.
+    InvalidHexDigit { column: usize },
+    /// The transmission ends at `bit`, while reading `bits` more.
+    UnexpectedEnd { bit: usize, bits: usize },
+    /// The literal value of a group at `bit` does not fit in 64 bits.
+    LiteralTooWide { bit: usize },
+    /// The sub-packets of an operator end at `bit`, past the length it
+    /// declares for them, which ends at `end`.
+    LengthOverflow { bit: usize, end: usize },
+    /// An operator at `bit` without sub-packets, or a comparison without
+    /// exactly two.
+    ChildCount { bit: usize, op: Op, count: usize },
+    /// A bit set at `bit` after the last packet, in what must be zero
+    /// padding.
+    NonZeroPadding { bit: usize },
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::InvalidHexDigit { column } => {
+                write!(f, "invalid hex digit at column {}", column)
+            }
+            DecodeError::UnexpectedEnd { bit, bits } => write!(
+                f,
+                "unexpected end of transmission at bit {}, reading {} bits",
+                bit, bits
+            ),
+            DecodeError::LiteralTooWide { bit } => {
+                write!(f, "literal value wider than 64 bits at bit {}", bit)
+            }
+            DecodeError::LengthOverflow { bit, end } => write!(
+                f,
+                "sub-packets end at bit {}, past their length ending at {}",
+                bit, end
+            ),
+            DecodeError::ChildCount { bit, op, count } => write!(
+                f,
+                "operator {:?} with {} sub-packets at bit {}",
+                op, count, bit
+            ),
+            DecodeError::NonZeroPadding { bit } => {
+                write!(f, "non-zero padding at bit {}", bit)
+            }
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<DecodeError> for Error {
+    fn from(err: DecodeError) -> Error {
+        Error::new(DAY, err.to_string())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PacketKind {
+    Literal(u64),
+    Operator(Op, Vec<Packet>),
+}
+
+/// The bits `start..end` of a transmission holding a packet, and the spans
+/// of its sub-packets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub children: Vec<Span>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    /// The version, which must fit in 3 bits.
+    pub version: u8,
+    pub kind: PacketKind,
 }
 
 impl Packet {
-    fn read(data: &str, p: &mut usize, len: usize) -> u16 {
-        let start = *p;
-        *p += len;
-        u16::from_str_radix(&data[start..*p], 2).unwrap()
+    pub fn literal(version: u8, value: u64) -> Packet {
+        let kind = PacketKind::Literal(value);
+        Packet { version, kind }
     }
 
-    fn read_literal_value(data: &str, p: &mut usize) -> u64 {
-        let mut value: u64 = 0;
+    pub fn operator(version: u8, op: Op, children: Vec<Packet>) -> Packet {
+        let kind = PacketKind::Operator(op, children);
+        Packet { version, kind }
+    }
+
+    pub fn type_id(&self) -> u8 {
+        match &self.kind {
+            PacketKind::Literal(_) => LITERAL,
+            PacketKind::Operator(op, _) => op.type_id(),
+        }
+    }
+
+    pub fn children(&self) -> &[Packet] {
+        match &self.kind {
+            PacketKind::Literal(_) => &[],
+            PacketKind::Operator(_, children) => children,
+        }
+    }
+
+    pub fn sum_of_versions(&self) -> usize {
+        self.fold(|packet, _, sums: Vec<usize>| {
+            packet.version as usize + sums.iter().sum::<usize>()
+        })
+    }
+
+    /// Computes a value for every packet from the values of its
+    /// sub-packets, given along with the indices of the sub-packets that
+    /// lead to it. The packets are walked with a stack of their own, so
+    /// that however deep they nest, they cannot overflow the call stack.
+    pub fn fold<T>(
+        &self,
+        mut f: impl FnMut(&Packet, &[usize], Vec<T>) -> T,
+    ) -> T {
+        let mut stack: Vec<(&Packet, usize)> = vec![(self, 0)];
+        let mut path: Vec<usize> = Vec::new();
+        let mut values: Vec<T> = Vec::new();
         loop {
-            let g = Self::read(data, p, 5);
-            let v = g & 0x0F;
-            value = value << 4 | v as u64;
-            if g <= 0x0F {
-                break;
+            let top = stack.len() - 1;
+            let (packet, next) = stack[top];
+            if let Some(child) = packet.children().get(next) {
+                stack[top].1 += 1;
+                path.push(next);
+                stack.push((child, 0));
+                continue;
+            }
+            let children = values.split_off(values.len() - next);
+            let value = f(packet, &path, children);
+            stack.pop();
+            if stack.is_empty() {
+                return value;
             }
+            path.pop();
+            values.push(value);
         }
-        value
     }
 
-    fn read_children(data: &str, p: &mut usize) -> Vec<Packet> {
-        let mut children: Vec<Packet> = Vec::new();
-        let len_id = Self::read(data, p, 1) as u8;
-        if len_id == 0 {
-            let len = Self::read(data, p, 15) as usize;
-            let end = *p + len;
-            while *p < end {
-                children.push(Self::read_packet(data, p));
+    /// Decodes the packet at the position of the reader.
+    pub fn read(reader: &mut BitReader) -> Result<Packet, DecodeError> {
+        Self::decode(reader, None)
+    }
+
+    /// Decodes the packet at the position of the reader, along with where
+    /// it and its sub-packets lie in the transmission.
+    pub fn read_spanned(
+        reader: &mut BitReader,
+    ) -> Result<(Packet, Span), DecodeError> {
+        let mut spans = Vec::with_capacity(1);
+        let packet = Self::decode(reader, Some(&mut spans))?;
+        Ok((packet, spans.remove(0)))
+    }
+
+    /// Decodes a packet, pushing its span if `spans` are wanted. The
+    /// operators being decoded are kept on a stack rather than recursed
+    /// into, so that deeply nested packets cannot overflow the call stack.
+    fn decode(
+        reader: &mut BitReader,
+        spans: Option<&mut Vec<Span>>,
+    ) -> Result<Packet, DecodeError> {
+        let spanned = spans.is_some();
+        let mut open: Vec<Open> = Vec::new();
+        loop {
+            let position = reader.position();
+            let (packet, span) = match open.last() {
+                Some(operator) if operator.is_complete(position) => {
+                    open.pop().unwrap().close(position)?
+                }
+                _ => {
+                    let start = position;
+                    let version = reader.read(3)? as u8;
+                    let type_id = reader.read(3)? as u8;
+                    if let Some(op) = Op::from_type_id(type_id) {
+                        let end = match reader.read(1)? {
+                            0 => {
+                                let len = reader.read(15)? as usize;
+                                Length::Bits(reader.position() + len)
+                            }
+                            _ => Length::Count(reader.read(11)? as usize),
+                        };
+                        open.push(Open {
+                            start,
+                            version,
+                            op,
+                            end,
+                            children: Vec::new(),
+                            spans: Vec::new(),
+                        });
+                        continue;
+                    }
+                    let value = Self::read_literal_value(reader)?;
+                    let span = Span {
+                        start,
+                        end: reader.position(),
+                        children: Vec::new(),
+                    };
+                    (Packet::literal(version, value), span)
+                }
+            };
+            match open.last_mut() {
+                Some(parent) => {
+                    parent.children.push(packet);
+                    if spanned {
+                        parent.spans.push(span);
+                    }
+                }
+                None => {
+                    if let Some(spans) = spans {
+                        spans.push(span);
+                    }
+                    return Ok(packet);
+                }
+            }
+        }
+    }
+
+    fn read_literal_value(
+        reader: &mut BitReader,
+    ) -> Result<u64, DecodeError> {
+        let mut value: u64 = 0;
+        loop {
+            let bit = reader.position();
+            let group = reader.read(5)?;
+            if value >> 60 != 0 {
+                return Err(DecodeError::LiteralTooWide { bit });
             }
-        } else {
-            let len = Self::read(data, p, 11) as usize;
-            for _ in 0..len {
-                children.push(Self::read_packet(data, p));
+            value = value << 4 | group & 0x0F;
+            if group <= 0x0F {
+                break;
             }
         }
-        children
+        Ok(value)
+    }
+
+    /// Decodes the packet of a hexadecimal transmission, which must be
+    /// followed by nothing but zero padding.
+    pub fn from_hex_str(hex: &str) -> Result<Packet, DecodeError> {
+        let (data, len) = bits::hex_to_bytes(hex)?;
+        let mut reader = BitReader::with_len(&data, len);
+        let packet = Self::read(&mut reader)?;
+        match reader.next_one() {
+            Some(bit) => Err(DecodeError::NonZeroPadding { bit }),
+            None => Ok(packet),
+        }
+    }
+
+    /// Decodes every top-level packet of a hexadecimal transmission.
+    pub fn all_from_hex_str(hex: &str) -> Result<Vec<Packet>, DecodeError> {
+        let (data, len) = bits::hex_to_bytes(hex)?;
+        Packets::new(BitReader::with_len(&data, len)).collect()
+    }
+
+    /// Encodes the packet as a hexadecimal transmission, padded with zeros
+    /// to a whole number of bytes.
+    ///
+    /// The sub-packets of an operator are counted (length type 1), which
+    /// takes fewer bits than their length, unless there are more than
+    /// 2047 of them (length type 0).
+    pub fn to_hex(&self) -> Result<String> {
+        let mut writer = BitWriter::new();
+        self.write(&mut writer)?;
+        Ok(bits::bytes_to_hex(&writer.into_bytes()))
     }
 
-    fn read_packet(data: &str, p: &mut usize) -> Packet {
-        let version = Self::read(data, p, 3) as u8;
-        let type_id = Self::read(data, p, 3) as u8;
-        if type_id == 4 {
-            let value = Self::read_literal_value(data, p);
-            return Packet {
-                version,
-                type_id,
-                value,
-                children: Vec::new(),
+    /// Encodes the packet at the end of the writer, without recursing
+    /// into sub-packets.
+    pub fn write(&self, writer: &mut BitWriter) -> Result<()> {
+        /// A packet to write, or the length in bits of sub-packets to
+        /// write at `at` once they are written.
+        enum Step<'a> {
+            Packet(&'a Packet),
+            Length { at: usize },
+        }
+
+        let mut steps = vec![Step::Packet(self)];
+        while let Some(step) = steps.pop() {
+            let packet = match step {
+                Step::Packet(packet) => packet,
+                Step::Length { at } => {
+                    let len = writer.len() - at - 15;
+                    if len >= 1 << 15 {
+                        let msg = "sub-packets do not fit in 15 bits";
+                        return Err(Error::new(DAY, msg));
+                    }
+                    writer.overwrite(at, len as u64, 15);
+                    continue;
+                }
+            };
+            if packet.version >= 8 {
+                let msg = format!(
+                    "version {} does not fit in 3 bits",
+                    packet.version
+                );
+                return Err(Error::new(DAY, msg));
+            }
+            writer.write(packet.version as u64, 3);
+            writer.write(packet.type_id() as u64, 3);
+            let (op, children) = match &packet.kind {
+                PacketKind::Literal(value) => {
+                    let groups =
+                        (64 - value.leading_zeros() as usize).div_ceil(4);
+                    for i in (0..groups.max(1)).rev() {
+                        let more = if i > 0 { 0x10 } else { 0 };
+                        writer.write(more | (value >> (i * 4)) & 0x0F, 5);
+                    }
+                    continue;
+                }
+                PacketKind::Operator(op, children) => (*op, children),
             };
+            check_children(op, children.len())?;
+            if children.len() < 1 << 11 {
+                writer.write(1, 1);
+                writer.write(children.len() as u64, 11);
+            } else {
+                writer.write(0, 1);
+                steps.push(Step::Length { at: writer.len() });
+                writer.write(0, 15);
+            }
+            steps.extend(children.iter().rev().map(Step::Packet));
         }
+        Ok(())
+    }
+}
 
-        let children = Self::read_children(data, p);
-        let subvalues: Vec<u64> = children.iter().map(|p| p.value).collect();
-        #[rustfmt::skip]
-        let value: u64 = match type_id {
-            0 => subvalues.into_iter().sum(),
-            1 => subvalues.into_iter().product(),
-            2 => subvalues.into_iter().min().unwrap(),
-            3 => subvalues.into_iter().max().unwrap(),
-            4 => unreachable!(),
-            5 => if subvalues[0] > subvalues[1]  { 1 } else { 0 },
-            6 => if subvalues[0] < subvalues[1]  { 1 } else { 0 },
-            7 => if subvalues[0] == subvalues[1] { 1 } else { 0 },
-            _ => unreachable!(),
+/// Drops the sub-packets from a stack of their own, as dropping them
+/// recursively could overflow the call stack.
+impl Drop for Packet {
+    fn drop(&mut self) {
+        let mut stack = match &mut self.kind {
+            PacketKind::Operator(_, children) => std::mem::take(children),
+            PacketKind::Literal(_) => return,
         };
+        while let Some(mut packet) = stack.pop() {
+            if let PacketKind::Operator(_, children) = &mut packet.kind {
+                stack.append(children);
+            }
+        }
+    }
+}
 
-        Packet {
-            version,
-            type_id,
-            value,
-            children,
+/// An operator being decoded.
+struct Open {
+    start: usize,
+    version: u8,
+    op: Op,
+    end: Length,
+    children: Vec<Packet>,
+    spans: Vec<Span>,
+}
+
+/// Where the sub-packets of an operator end: at a bit, or after a number
+/// of them.
+enum Length {
+    Bits(usize),
+    Count(usize),
+}
+
+impl Open {
+    fn is_complete(&self, position: usize) -> bool {
+        match self.end {
+            Length::Bits(end) => position >= end,
+            Length::Count(count) => self.children.len() == count,
         }
     }
 
-    fn from_binary_str(data: &str) -> Packet {
-        let mut p = 0;
-        Self::read_packet(data, &mut p)
+    /// The operator, once its sub-packets end at `position`.
+    fn close(self, position: usize) -> Result<(Packet, Span), DecodeError> {
+        if let Length::Bits(end) = self.end {
+            if position > end {
+                let bit = position;
+                return Err(DecodeError::LengthOverflow { bit, end });
+            }
+        }
+        let (op, count) = (self.op, self.children.len());
+        if !has_valid_children(op, count) {
+            let bit = self.start;
+            return Err(DecodeError::ChildCount { bit, op, count });
+        }
+        let span = Span {
+            start: self.start,
+            end: position,
+            children: self.spans,
+        };
+        Ok((Packet::operator(self.version, op, self.children), span))
+    }
+}
+
+/// Decodes consecutive top-level packets, until nothing but zero padding
+/// is left. Decoding stops at the first error.
+pub struct Packets<'a> {
+    reader: BitReader<'a>,
+    failed: bool,
+}
+
+impl<'a> Packets<'a> {
+    pub fn new(reader: BitReader<'a>) -> Packets<'a> {
+        Packets {
+            reader,
+            failed: false,
+        }
     }
 
-    fn from_hex_str(hex: &str) -> Packet {
-        let data = parse_input(hex);
-        Self::from_binary_str(&data)
+    /// Decodes the next packet, along with where it lies in the
+    /// transmission.
+    pub fn next_spanned(
+        &mut self,
+    ) -> Option<Result<(Packet, Span), DecodeError>> {
+        let mut spans = Vec::with_capacity(1);
+        let packet = self.advance(Some(&mut spans))?;
+        Some(packet.map(|packet| (packet, spans.remove(0))))
     }
 
-    fn sum_of_versions(&self) -> usize {
-        let mut sum = self.version as usize;
-        for packet in self.children.iter() {
-            sum += packet.sum_of_versions();
+    fn advance(
+        &mut self,
+        spans: Option<&mut Vec<Span>>,
+    ) -> Option<Result<Packet, DecodeError>> {
+        if self.failed {
+            return None;
         }
-        sum
+        let bit = self.reader.next_one()?;
+        let packet = match self.reader.remaining() {
+            n if n < MIN_PACKET_BITS => {
+                Err(DecodeError::NonZeroPadding { bit })
+            }
+            _ => Packet::decode(&mut self.reader, spans),
+        };
+        self.failed = packet.is_err();
+        Some(packet)
+    }
+}
+
+impl Iterator for Packets<'_> {
+    type Item = Result<Packet, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.advance(None)
+    }
+}
+
+/// Whether an operator has sub-packets, and exactly two if it is a
+/// comparison.
+fn has_valid_children(op: Op, count: usize) -> bool {
+    count > 0 && (!op.is_comparison() || count == 2)
+}
+
+fn check_children(op: Op, count: usize) -> Result<()> {
+    if !has_valid_children(op, count) {
+        let msg = format!("operator {:?} with {} sub-packets", op, count);
+        return Err(Error::new(DAY, msg));
     }
+    Ok(())
 }
 
-pub fn part_one(input: &str) -> usize {
-    Packet::from_hex_str(input).sum_of_versions()
+pub fn part_one(input: &Input) -> Result<usize> {
+    Ok(input.packet.sum_of_versions())
 }
 
-pub fn part_two(input: &str) -> u64 {
-    Packet::from_hex_str(input).value
+pub fn part_two(input: &Input) -> Result<u64> {
+    input.packet.value()
+}
+
+pub fn solve(input: &Input) -> Result<(usize, u64)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -142,31 +579,246 @@ mod tests {
 
     #[test]
     fn decoder() {
-        let packet = Packet::from_hex_str("D2FE28");
-        assert_eq!(packet.value, 2021);
-        let packet = Packet::from_hex_str("38006F45291200");
-        assert_eq!(packet.children.len(), 2);
-        let packet = Packet::from_hex_str("EE00D40C823060");
-        assert_eq!(packet.children.len(), 3);
+        let packet = Packet::from_hex_str("D2FE28").unwrap();
+        assert_eq!(packet, Packet::literal(6, 2021));
+        let packet = Packet::from_hex_str("38006F45291200").unwrap();
+        assert_eq!(packet.kind, {
+            let children =
+                vec![Packet::literal(6, 10), Packet::literal(2, 20)];
+            PacketKind::Operator(Op::LessThan, children)
+        });
+        let packet = Packet::from_hex_str("EE00D40C823060").unwrap();
+        assert_eq!(packet.type_id(), 3);
+        assert_eq!(packet.children().len(), 3);
+    }
+
+    #[test]
+    fn encoder() {
+        assert_eq!(Packet::literal(6, 2021).to_hex().unwrap(), "D2FE28");
+        let packet = Packet::from_hex_str("C200B40A82").unwrap();
+        assert_eq!(packet.to_hex().unwrap(), "C200B40A82");
+        assert!(Packet::literal(8, 1).to_hex().is_err());
+        assert!(Packet::operator(0, Op::EqualTo, vec![]).to_hex().is_err());
+
+        let children = vec![Packet::literal(0, 1); 2500];
+        let packet = Packet::operator(0, Op::Sum, children);
+        let hex = packet.to_hex().unwrap();
+        assert_eq!(Packet::from_hex_str(&hex).unwrap(), packet);
+    }
+
+    /// A xorshift generator, to build random packets.
+    struct Random(u64);
+
+    impl Random {
+        fn next(&mut self, n: u64) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0 % n
+        }
+
+        fn packet(&mut self, depth: usize) -> Packet {
+            let version = self.next(8) as u8;
+            if depth == 0 || self.next(3) == 0 {
+                let bits = self.next(65);
+                let value = self.next(u64::MAX) >> (64 - bits).min(63);
+                return Packet::literal(version, value);
+            }
+            let op = loop {
+                if let Some(op) = Op::from_type_id(self.next(8) as u8) {
+                    break op;
+                }
+            };
+            let count = match op.is_comparison() {
+                true => 2,
+                false => 1 + self.next(4) as usize,
+            };
+            let children = (0..count).map(|_| self.packet(depth - 1));
+            Packet::operator(version, op, children.collect())
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut random = Random(0x2021_1216);
+        for _ in 0..500 {
+            let packet = random.packet(4);
+            let hex = packet.to_hex().unwrap();
+            assert_eq!(
+                Packet::from_hex_str(&hex).unwrap(),
+                packet,
+                "{}",
+                hex
+            );
+        }
+        for hex in ["8A004A801A8002F478", "9C0141080250320F1802104A08"] {
+            let packet = Packet::from_hex_str(hex).unwrap();
+            let hex = packet.to_hex().unwrap();
+            assert_eq!(Packet::from_hex_str(&hex).unwrap(), packet);
+        }
+    }
+
+    #[test]
+    fn invalid_input() {
+        let err = Packet::from_hex_str("D2FG28").unwrap_err();
+        assert_eq!(err, DecodeError::InvalidHexDigit { column: 4 });
+        let err = Packet::from_hex_str("D2").unwrap_err();
+        assert_eq!(err, DecodeError::UnexpectedEnd { bit: 6, bits: 5 });
+        let err = Packet::from_hex_str("D2FE29").unwrap_err();
+        assert_eq!(err, DecodeError::NonZeroPadding { bit: 23 });
+
+        // A sum without sub-packets, and a comparison with only one.
+        let err = Packet::from_hex_str("020000").unwrap_err();
+        let op = Op::Sum;
+        assert_eq!(
+            err,
+            DecodeError::ChildCount {
+                bit: 0,
+                op,
+                count: 0
+            }
+        );
+        let mut writer = BitWriter::new();
+        writer.write(0, 3);
+        writer.write(Op::GreaterThan.type_id() as u64, 3);
+        writer.write(1, 1);
+        writer.write(1, 11);
+        Packet::literal(0, 1).write(&mut writer).unwrap();
+        let hex = bits::bytes_to_hex(&writer.into_bytes());
+        let err = Packet::from_hex_str(&hex).unwrap_err();
+        let op = Op::GreaterThan;
+        assert_eq!(
+            err,
+            DecodeError::ChildCount {
+                bit: 0,
+                op,
+                count: 1
+            }
+        );
+
+        // A literal of 11 bits, in a length of 10.
+        let mut writer = BitWriter::new();
+        writer.write(0, 7);
+        writer.write(10, 15);
+        Packet::literal(0, 1).write(&mut writer).unwrap();
+        let hex = bits::bytes_to_hex(&writer.into_bytes());
+        let err = Packet::from_hex_str(&hex).unwrap_err();
+        assert_eq!(err, DecodeError::LengthOverflow { bit: 33, end: 32 });
+
+        let err = parse("D2").unwrap_err();
+        assert_eq!(
+            err.message,
+            "unexpected end of transmission at bit 6, reading 5 bits"
+        );
+    }
+
+    #[test]
+    fn stream() {
+        let mut writer = BitWriter::new();
+        for hex in ["D2FE28", "38006F45291200", "C200B40A82"] {
+            Packet::from_hex_str(hex)
+                .unwrap()
+                .write(&mut writer)
+                .unwrap();
+        }
+        let hex = bits::bytes_to_hex(&writer.into_bytes());
+        let packets = Packet::all_from_hex_str(&hex).unwrap();
+        let values: Vec<u64> =
+            packets.iter().map(|p| p.value().unwrap()).collect();
+        assert_eq!(values, [2021, 1, 3]);
+        assert_eq!(Packet::all_from_hex_str("0000").unwrap(), []);
+        assert_eq!(
+            Packet::all_from_hex_str("D2FE2800").unwrap(),
+            [Packet::literal(6, 2021)]
+        );
+
+        // Too few bits left for another packet.
+        let err = Packet::all_from_hex_str("D2FE29").unwrap_err();
+        assert_eq!(err, DecodeError::NonZeroPadding { bit: 23 });
+        // Enough bits for another packet, but not for the one they start.
+        let err = Packet::all_from_hex_str("D2FE28D2").unwrap_err();
+        assert_eq!(err, DecodeError::UnexpectedEnd { bit: 28, bits: 11 });
+
+        let mut writer = BitWriter::new();
+        Packet::literal(6, 2021).write(&mut writer).unwrap();
+        Packet::literal(6, 2021).write(&mut writer).unwrap();
+        let data = writer.into_bytes();
+        let mut packets = Packets::new(BitReader::new(&data));
+        let (_, span) = packets.next_spanned().unwrap().unwrap();
+        assert_eq!((span.start, span.end), (0, 21));
+        let (_, span) = packets.next_spanned().unwrap().unwrap();
+        assert_eq!((span.start, span.end), (21, 42));
+        assert!(packets.next().is_none());
+    }
+
+    #[test]
+    fn wide_literals() {
+        let packet = Packet::literal(0, u64::MAX);
+        let hex = packet.to_hex().unwrap();
+        assert_eq!(Packet::from_hex_str(&hex).unwrap(), packet);
+
+        let mut writer = BitWriter::new();
+        writer.write(4, 6);
+        for _ in 0..16 {
+            writer.write(0x1F, 5);
+        }
+        writer.write(0x01, 5);
+        let hex = bits::bytes_to_hex(&writer.into_bytes());
+        let err = Packet::from_hex_str(&hex).unwrap_err();
+        assert_eq!(err, DecodeError::LiteralTooWide { bit: 86 });
+    }
+
+    #[test]
+    fn deep_nesting() {
+        let depth = 100_000;
+        let mut packet = Packet::literal(1, 1);
+        for _ in 0..depth {
+            packet = Packet::operator(1, Op::Sum, vec![packet]);
+        }
+        let hex = packet.to_hex().unwrap();
+        let decoded = Packet::from_hex_str(&hex).unwrap();
+        assert_eq!(decoded.to_hex().unwrap(), hex);
+        assert_eq!(decoded.sum_of_versions(), depth + 1);
+        assert_eq!(decoded.checked_value(), Ok(1));
+        assert_eq!(decoded.big_value().unwrap().to_string(), "1");
+        let expr = "(+ ".repeat(depth) + "1" + &")".repeat(depth);
+        assert_eq!(decoded.to_string(), expr);
     }
 
     #[test]
     fn example_one() {
-        assert_eq!(part_one("8A004A801A8002F478"), 16);
-        assert_eq!(part_one("620080001611562C8802118E34"), 12);
-        assert_eq!(part_one("C0015000016115A2E0802F182340"), 23);
-        assert_eq!(part_one("A0016C880162017C3686B18A3D4780"), 31);
+        assert_eq!(
+            part_one(&parse("8A004A801A8002F478").unwrap()).unwrap(),
+            16
+        );
+        assert_eq!(
+            part_one(&parse("620080001611562C8802118E34").unwrap()).unwrap(),
+            12
+        );
+        assert_eq!(
+            part_one(&parse("C0015000016115A2E0802F182340").unwrap())
+                .unwrap(),
+            23
+        );
+        assert_eq!(
+            part_one(&parse("A0016C880162017C3686B18A3D4780").unwrap())
+                .unwrap(),
+            31
+        );
     }
 
     #[test]
     fn example_two() {
-        assert_eq!(part_two("C200B40A82"), 3);
-        assert_eq!(part_two("04005AC33890"), 54);
-        assert_eq!(part_two("880086C3E88112"), 7);
-        assert_eq!(part_two("CE00C43D881120"), 9);
-        assert_eq!(part_two("D8005AC2A8F0"), 1);
-        assert_eq!(part_two("F600BC2D8F"), 0);
-        assert_eq!(part_two("9C005AC2F8F0"), 0);
-        assert_eq!(part_two("9C0141080250320F1802104A08"), 1);
+        assert_eq!(part_two(&parse("C200B40A82").unwrap()).unwrap(), 3);
+        assert_eq!(part_two(&parse("04005AC33890").unwrap()).unwrap(), 54);
+        assert_eq!(part_two(&parse("880086C3E88112").unwrap()).unwrap(), 7);
+        assert_eq!(part_two(&parse("CE00C43D881120").unwrap()).unwrap(), 9);
+        assert_eq!(part_two(&parse("D8005AC2A8F0").unwrap()).unwrap(), 1);
+        assert_eq!(part_two(&parse("F600BC2D8F").unwrap()).unwrap(), 0);
+        assert_eq!(part_two(&parse("9C005AC2F8F0").unwrap()).unwrap(), 0);
+        assert_eq!(
+            part_two(&parse("9C0141080250320F1802104A08").unwrap()).unwrap(),
+            1
+        );
     }
 }
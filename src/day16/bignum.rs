@@ -0,0 +1,173 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul};
+
+/// An unsigned integer of any size, so that packets evaluate exactly.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    /// Digits in base 2^32, least significant first, without leading
+    /// zeros, so that zero has none.
+    digits: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn zero() -> BigUint {
+        BigUint::default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    /// The number, if it fits in 64 bits.
+    pub fn to_u64(&self) -> Option<u64> {
+        match self.digits[..] {
+            [] => Some(0),
+            [low] => Some(low as u64),
+            [low, high] => Some((high as u64) << 32 | low as u64),
+            _ => None,
+        }
+    }
+
+    fn trim(mut self) -> BigUint {
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+        self
+    }
+
+    /// Divides in place by a single digit, returning the remainder.
+    fn div_rem_digit(&mut self, divisor: u32) -> u32 {
+        let mut rem: u64 = 0;
+        for digit in self.digits.iter_mut().rev() {
+            let n = rem << 32 | *digit as u64;
+            *digit = (n / divisor as u64) as u32;
+            rem = n % divisor as u64;
+        }
+        while self.digits.last() == Some(&0) {
+            self.digits.pop();
+        }
+        rem as u32
+    }
+}
+
+impl From<u64> for BigUint {
+    fn from(n: u64) -> BigUint {
+        let digits = vec![n as u32, (n >> 32) as u32];
+        BigUint { digits }.trim()
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &BigUint) -> Ordering {
+        self.digits.len().cmp(&other.digits.len()).then_with(|| {
+            self.digits.iter().rev().cmp(other.digits.iter().rev())
+        })
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &BigUint) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Add for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let len = self.digits.len().max(other.digits.len());
+        let mut digits = Vec::with_capacity(len + 1);
+        let mut carry: u64 = 0;
+        for i in 0..len {
+            let a = self.digits.get(i).copied().unwrap_or(0) as u64;
+            let b = other.digits.get(i).copied().unwrap_or(0) as u64;
+            let sum = a + b + carry;
+            digits.push(sum as u32);
+            carry = sum >> 32;
+        }
+        digits.push(carry as u32);
+        BigUint { digits }.trim()
+    }
+}
+
+impl Mul for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        let mut digits = vec![0u32; self.digits.len() + other.digits.len()];
+        for (i, &a) in self.digits.iter().enumerate() {
+            let mut carry: u64 = 0;
+            for (j, &b) in other.digits.iter().enumerate() {
+                let n = a as u64 * b as u64 + digits[i + j] as u64 + carry;
+                digits[i + j] = n as u32;
+                carry = n >> 32;
+            }
+            digits[i + other.digits.len()] = carry as u32;
+        }
+        BigUint { digits }.trim()
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const BILLION: u32 = 1_000_000_000;
+        let mut n = self.clone();
+        let mut chunks = Vec::new();
+        while !n.is_zero() {
+            chunks.push(n.div_rem_digit(BILLION));
+        }
+        match chunks.pop() {
+            Some(first) => write!(f, "{}", first)?,
+            None => return write!(f, "0"),
+        }
+        for chunk in chunks.iter().rev() {
+            write!(f, "{:09}", chunk)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arithmetic() {
+        let max = BigUint::from(u64::MAX);
+        assert_eq!(max.to_u64(), Some(u64::MAX));
+        let sum = &max + &BigUint::from(1);
+        assert_eq!(sum.to_string(), "18446744073709551616");
+        assert_eq!(sum.to_u64(), None);
+        let square = &sum * &sum;
+        assert_eq!(
+            square.to_string(),
+            "340282366920938463463374607431768211456"
+        );
+        assert_eq!(&square * &BigUint::zero(), BigUint::zero());
+        assert_eq!(BigUint::zero().to_string(), "0");
+        assert_eq!(BigUint::from(1_000_000_007).to_string(), "1000000007");
+    }
+
+    #[test]
+    fn ordering() {
+        let mut numbers: Vec<BigUint> = [7, 0, u64::MAX, 1 << 32]
+            .into_iter()
+            .map(BigUint::from)
+            .collect();
+        numbers.push(&numbers[2] * &numbers[2]);
+        numbers.sort();
+        let sorted: Vec<String> =
+            numbers.iter().map(|n| n.to_string()).collect();
+        assert_eq!(
+            sorted,
+            [
+                "0",
+                "7",
+                "4294967296",
+                "18446744073709551615",
+                "340282366920938463426481119284349108225"
+            ]
+        );
+    }
+}
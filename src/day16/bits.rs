@@ -0,0 +1,201 @@
+use super::DecodeError;
+
+/// Reads a transmission packed into bytes, most significant bit first.
+pub struct BitReader<'a> {
+    data: &'a [u8],
+    len: usize,
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(data: &'a [u8]) -> BitReader<'a> {
+        BitReader::with_len(data, data.len() * 8)
+    }
+
+    /// Reads only the first `len` bits of `data`.
+    pub fn with_len(data: &'a [u8], len: usize) -> BitReader<'a> {
+        let len = len.min(data.len() * 8);
+        BitReader {
+            data,
+            len,
+            position: 0,
+        }
+    }
+
+    /// The offset of the next bit to read.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.len - self.position
+    }
+
+    /// The offset of the next bit set to one, if any, without reading.
+    pub fn next_one(&self) -> Option<usize> {
+        (self.position..self.len)
+            .find(|&i| (self.data[i / 8] << (i % 8)) & 0x80 != 0)
+    }
+
+    /// Reads a number of up to 64 bits.
+    pub fn read(&mut self, bits: usize) -> Result<u64, DecodeError> {
+        assert!(bits <= 64);
+        if bits > self.remaining() {
+            let bit = self.position;
+            return Err(DecodeError::UnexpectedEnd { bit, bits });
+        }
+        let mut value: u64 = 0;
+        let mut bits = bits;
+        while bits > 0 {
+            let byte = self.data[self.position / 8];
+            let offset = self.position % 8;
+            let take = bits.min(8 - offset);
+            let chunk = (byte << offset) >> (8 - take);
+            value = (value << take) | chunk as u64;
+            self.position += take;
+            bits -= take;
+        }
+        Ok(value)
+    }
+}
+
+/// Packs bits into bytes, most significant bit first.
+#[derive(Debug, Default)]
+pub struct BitWriter {
+    data: Vec<u8>,
+    len: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> BitWriter {
+        BitWriter::default()
+    }
+
+    /// The number of bits written.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Writes the lowest `bits` bits of `value`.
+    pub fn write(&mut self, value: u64, bits: usize) {
+        assert!(bits <= 64);
+        for i in (0..bits).rev() {
+            if self.len.is_multiple_of(8) {
+                self.data.push(0);
+            }
+            if value >> i & 1 == 1 {
+                *self.data.last_mut().unwrap() |= 0x80 >> (self.len % 8);
+            }
+            self.len += 1;
+        }
+    }
+
+    /// Writes the lowest `bits` bits of `value` over those written from
+    /// position `at`.
+    pub fn overwrite(&mut self, at: usize, value: u64, bits: usize) {
+        assert!(bits <= 64 && at + bits <= self.len);
+        for i in 0..bits {
+            let pos = at + bits - 1 - i;
+            let mask = 0x80 >> (pos % 8);
+            match value >> i & 1 {
+                1 => self.data[pos / 8] |= mask,
+                _ => self.data[pos / 8] &= !mask,
+            }
+        }
+    }
+
+    /// Appends everything written to another writer.
+    pub fn append(&mut self, other: &BitWriter) {
+        let mut reader = BitReader::with_len(&other.data, other.len);
+        while reader.remaining() > 0 {
+            let bits = reader.remaining().min(64);
+            self.write(reader.read(bits).unwrap(), bits);
+        }
+    }
+
+    /// Returns the bytes, the last one padded with zeros.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+/// Packs hexadecimal digits into bytes, returning them and the number of
+/// bits they hold.
+pub fn hex_to_bytes(hex: &str) -> Result<(Vec<u8>, usize), DecodeError> {
+    let hex = hex.trim();
+    let mut data = Vec::with_capacity(hex.len() / 2 + 1);
+    for (i, c) in hex.bytes().enumerate() {
+        let nibble = match c {
+            b'0'..=b'9' => c - b'0',
+            b'A'..=b'F' => c - b'A' + 10,
+            _ => {
+                let column = i + 1;
+                return Err(DecodeError::InvalidHexDigit { column });
+            }
+        };
+        match i % 2 {
+            0 => data.push(nibble << 4),
+            _ => *data.last_mut().unwrap() |= nibble,
+        }
+    }
+    Ok((data, hex.len() * 4))
+}
+
+pub fn bytes_to_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read() {
+        let (data, len) = hex_to_bytes("D2FE28").unwrap();
+        assert_eq!(len, 24);
+        let mut reader = BitReader::new(&data);
+        assert_eq!(reader.read(3).unwrap(), 6);
+        assert_eq!(reader.read(3).unwrap(), 4);
+        assert_eq!(reader.read(15).unwrap(), 0b101111111000101);
+        assert_eq!(reader.position(), 21);
+        let err = reader.read(4).unwrap_err();
+        assert_eq!(err, DecodeError::UnexpectedEnd { bit: 21, bits: 4 });
+        assert_eq!(reader.next_one(), None);
+
+        let data = [0xFF; 9];
+        let mut reader = BitReader::new(&data);
+        assert_eq!(reader.read(4).unwrap(), 0xF);
+        assert_eq!(reader.next_one(), Some(4));
+        assert_eq!(reader.read(64).unwrap(), u64::MAX);
+
+        let (data, len) = hex_to_bytes("ABC").unwrap();
+        let mut reader = BitReader::with_len(&data, len);
+        assert_eq!(reader.read(12).unwrap(), 0xABC);
+        assert_eq!(reader.remaining(), 0);
+        assert_eq!(reader.next_one(), None);
+        assert_eq!(
+            hex_to_bytes("D2fE28").unwrap_err(),
+            DecodeError::InvalidHexDigit { column: 3 }
+        );
+    }
+
+    #[test]
+    fn write() {
+        let mut writer = BitWriter::new();
+        writer.write(6, 3);
+        writer.write(4, 3);
+        let mut literal = BitWriter::new();
+        literal.write(0b101111111000101, 15);
+        writer.append(&literal);
+        assert_eq!(writer.len(), 21);
+        writer.overwrite(0, 0, 3);
+        writer.overwrite(6, 0x3FF, 10);
+        writer.overwrite(0, 6, 3);
+        writer.overwrite(6, 0b1011111110, 10);
+        assert_eq!(bytes_to_hex(&writer.into_bytes()), "D2FE28");
+    }
+}
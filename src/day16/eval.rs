@@ -0,0 +1,239 @@
+use std::fmt;
+
+use super::bignum::BigUint;
+use super::{has_valid_children, Op, Packet, PacketKind, DAY};
+use crate::{Error, Result};
+
+/// Why a packet could not be evaluated, and which one: `path` holds the
+/// index of a sub-packet at each level down from the outermost packet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    /// The value of an operator does not fit in 64 bits.
+    Overflow { path: Vec<usize>, op: Op },
+    /// An operator without sub-packets, or a comparison without exactly
+    /// two.
+    ChildCount {
+        path: Vec<usize>,
+        op: Op,
+        count: usize,
+    },
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = match self {
+            EvalError::Overflow { path, .. } => path,
+            EvalError::ChildCount { path, .. } => path,
+        };
+        match path.split_first() {
+            Some((first, rest)) => {
+                write!(f, "sub-packet {}", first)?;
+                for i in rest {
+                    write!(f, ".{}", i)?;
+                }
+            }
+            None => write!(f, "outermost packet")?,
+        }
+        match self {
+            EvalError::Overflow { op, .. } => {
+                write!(f, ": {} overflows 64 bits", op.name())
+            }
+            EvalError::ChildCount { op, count, .. } => {
+                write!(f, ": operator {:?} with {} sub-packets", op, count)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+impl From<EvalError> for Error {
+    fn from(err: EvalError) -> Error {
+        Error::new(DAY, err.to_string())
+    }
+}
+
+/// A number that packets evaluate to.
+pub(super) trait Number: Ord + Sized {
+    fn from_u64(n: u64) -> Self;
+    fn try_add(self, other: Self) -> Option<Self>;
+    fn try_mul(self, other: Self) -> Option<Self>;
+}
+
+impl Number for u64 {
+    fn from_u64(n: u64) -> u64 {
+        n
+    }
+
+    fn try_add(self, other: u64) -> Option<u64> {
+        self.checked_add(other)
+    }
+
+    fn try_mul(self, other: u64) -> Option<u64> {
+        self.checked_mul(other)
+    }
+}
+
+impl Number for BigUint {
+    fn from_u64(n: u64) -> BigUint {
+        BigUint::from(n)
+    }
+
+    fn try_add(self, other: BigUint) -> Option<BigUint> {
+        Some(&self + &other)
+    }
+
+    fn try_mul(self, other: BigUint) -> Option<BigUint> {
+        Some(&self * &other)
+    }
+}
+
+impl Packet {
+    /// Evaluates the expression the packet represents.
+    pub fn value(&self) -> Result<u64> {
+        Ok(self.checked_value()?)
+    }
+
+    /// Evaluates the expression the packet represents, failing with the
+    /// path of the first operator whose value does not fit in 64 bits.
+    pub fn checked_value(&self) -> Result<u64, EvalError> {
+        evaluate(self)
+    }
+
+    /// Evaluates the expression the packet represents exactly, however
+    /// large its value.
+    pub fn big_value(&self) -> Result<BigUint, EvalError> {
+        evaluate(self)
+    }
+}
+
+fn evaluate<N: Number>(packet: &Packet) -> Result<N, EvalError> {
+    packet.fold(apply)
+}
+
+/// The value of a packet, from the values of its sub-packets, failing with
+/// the first error among them if any.
+pub(super) fn apply<N: Number>(
+    packet: &Packet,
+    path: &[usize],
+    values: Vec<Result<N, EvalError>>,
+) -> Result<N, EvalError> {
+    let (op, count) = match &packet.kind {
+        PacketKind::Literal(value) => return Ok(N::from_u64(*value)),
+        PacketKind::Operator(op, children) => (*op, children.len()),
+    };
+    if !has_valid_children(op, count) {
+        let path = path.to_vec();
+        return Err(EvalError::ChildCount { path, op, count });
+    }
+    let mut values = values
+        .into_iter()
+        .collect::<Result<Vec<N>, EvalError>>()?
+        .into_iter();
+    let first = values.next().unwrap();
+    let value = match op {
+        Op::Sum => values.try_fold(first, N::try_add),
+        Op::Product => values.try_fold(first, N::try_mul),
+        Op::Minimum => Some(values.fold(first, N::min)),
+        Op::Maximum => Some(values.fold(first, N::max)),
+        Op::GreaterThan | Op::LessThan | Op::EqualTo => {
+            let second = values.next().unwrap();
+            let holds = match op {
+                Op::GreaterThan => first > second,
+                Op::LessThan => first < second,
+                _ => first == second,
+            };
+            Some(N::from_u64(holds as u64))
+        }
+    };
+    value.ok_or_else(|| {
+        let path = path.to_vec();
+        EvalError::Overflow { path, op }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn overflow() {
+        let max = Packet::literal(0, u64::MAX);
+        let two = Packet::literal(0, 2);
+        let product = Packet::operator(0, Op::Product, vec![max, two]);
+        let sum = Packet::operator(
+            0,
+            Op::Sum,
+            vec![Packet::literal(0, 1), product.clone()],
+        );
+        let err = sum.checked_value().unwrap_err();
+        assert_eq!(
+            err,
+            EvalError::Overflow {
+                path: vec![1],
+                op: Op::Product
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "sub-packet 1: product overflows 64 bits"
+        );
+        assert_eq!(
+            sum.big_value().unwrap().to_string(),
+            "36893488147419103231"
+        );
+
+        let err = super::super::parse(&sum.to_hex().unwrap())
+            .and_then(|input| super::super::part_two(&input))
+            .unwrap_err();
+        assert!(err.message.contains("sub-packet 1:"), "{}", err);
+
+        let less = Packet::operator(
+            0,
+            Op::LessThan,
+            vec![Packet::literal(0, u64::MAX), product],
+        );
+        assert_eq!(less.big_value().unwrap().to_u64(), Some(1));
+        assert!(less.checked_value().is_err());
+    }
+
+    #[test]
+    fn child_count() {
+        let empty = Packet::operator(0, Op::Minimum, vec![]);
+        let equal = Packet::operator(0, Op::EqualTo, vec![empty]);
+        let err = equal.checked_value().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "outermost packet: operator EqualTo with 1 sub-packets"
+        );
+        let max = Packet::operator(0, Op::Maximum, vec![equal]);
+        let err = max.big_value().unwrap_err();
+        assert_eq!(
+            err,
+            EvalError::ChildCount {
+                path: vec![0],
+                op: Op::EqualTo,
+                count: 1
+            }
+        );
+    }
+
+    #[test]
+    fn same_values() {
+        for hex in ["C200B40A82", "04005AC33890", "880086C3E88112"] {
+            let packet = Packet::from_hex_str(hex).unwrap();
+            let value = packet.checked_value().unwrap();
+            assert_eq!(packet.big_value().unwrap().to_u64(), Some(value));
+        }
+    }
+
+    #[test]
+    fn single_sub_packet() {
+        for op in [Op::Sum, Op::Product, Op::Minimum, Op::Maximum] {
+            let seven = Packet::literal(0, 7);
+            let packet = Packet::operator(0, op, vec![seven]);
+            assert_eq!(packet.checked_value(), Ok(7), "{:?}", op);
+            assert_eq!(packet.big_value().unwrap().to_u64(), Some(7));
+        }
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt;
+use std::fmt::Write;
+
+use super::bignum::BigUint;
+use super::eval::{apply, EvalError};
+use super::{Packet, PacketKind, Span};
+
+/// Formats the expression of the packet as an S-expression, such as
+/// `(+ 1 (* 2 3))`.
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // a packet to write, or the end of an operator
+        let mut stack = vec![Some(self)];
+        let mut first = true;
+        while let Some(token) = stack.pop() {
+            let packet = match token {
+                Some(packet) => packet,
+                None => {
+                    write!(f, ")")?;
+                    continue;
+                }
+            };
+            if !first {
+                write!(f, " ")?;
+            }
+            first = false;
+            match &packet.kind {
+                PacketKind::Literal(value) => write!(f, "{}", value)?,
+                PacketKind::Operator(op, children) => {
+                    write!(f, "({}", op.symbol())?;
+                    stack.push(None);
+                    stack.extend(children.iter().rev().map(Some));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Renders the packet as an indented tree, a line per packet with its
+/// version, its type and its value, along with its bits in the
+/// transmission if the span is given.
+///
+/// ```text
+/// sum v6 = 3, bits 0..40
+///   literal v6 = 1, bits 18..29
+///   literal v2 = 2, bits 29..40
+/// ```
+pub fn tree(packet: &Packet, span: Option<&Span>) -> String {
+    // The value and the number of packets of every packet, in the order
+    // they are folded, each packet after its sub-packets.
+    let mut values: Vec<Result<BigUint, EvalError>> = Vec::new();
+    let mut sizes: Vec<usize> = Vec::new();
+    let root = packet.fold(|packet, path, children: Vec<usize>| {
+        let args = children.iter().map(|&i| values[i].clone()).collect();
+        values.push(apply(packet, path, args));
+        sizes.push(1 + children.iter().map(|&i| sizes[i]).sum::<usize>());
+        values.len() - 1
+    });
+
+    let mut s = String::new();
+    let mut stack = vec![(packet, span, root, 0)];
+    while let Some((packet, span, index, depth)) = stack.pop() {
+        let name = match &packet.kind {
+            PacketKind::Literal(_) => "literal",
+            PacketKind::Operator(op, _) => op.name(),
+        };
+        s.extend(std::iter::repeat_n(' ', depth * 2));
+        let _ = write!(s, "{} v{}", name, packet.version);
+        match &values[index] {
+            Ok(value) => {
+                let _ = write!(s, " = {}", value);
+            }
+            Err(err) => {
+                let _ = write!(s, " = error: {}", err);
+            }
+        }
+        if let Some(span) = span {
+            let _ = write!(s, ", bits {}..{}", span.start, span.end);
+        }
+        s.push('\n');
+        // the sub-packets come right before the packet, the last one
+        // first
+        let mut end = index;
+        for (i, child) in packet.children().iter().enumerate().rev() {
+            let child_span = span.and_then(|span| span.children.get(i));
+            stack.push((child, child_span, end - 1, depth + 1));
+            end -= sizes[end - 1];
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::bits::{self, BitReader};
+    use super::super::Op;
+    use super::*;
+
+    #[test]
+    fn sexpr() {
+        let product = Packet::operator(
+            0,
+            Op::Product,
+            vec![Packet::literal(0, 2), Packet::literal(0, 3)],
+        );
+        let packet = Packet::operator(
+            0,
+            Op::Sum,
+            vec![Packet::literal(0, 1), product],
+        );
+        assert_eq!(packet.to_string(), "(+ 1 (* 2 3))");
+        let packet = Packet::from_hex_str("9C0141080250320F1802104A08");
+        assert_eq!(packet.unwrap().to_string(), "(= (+ 1 3) (* 2 2))");
+    }
+
+    #[test]
+    fn tree() {
+        let (data, len) = bits::hex_to_bytes("C200B40A82").unwrap();
+        let mut reader = BitReader::with_len(&data, len);
+        let (packet, span) = Packet::read_spanned(&mut reader).unwrap();
+        assert_eq!(
+            super::tree(&packet, Some(&span)),
+            "sum v6 = 3, bits 0..40\n  \
+               literal v6 = 1, bits 18..29\n  \
+               literal v2 = 2, bits 29..40\n"
+        );
+        assert_eq!(
+            super::tree(&packet.children()[0], None),
+            "literal v6 = 1\n"
+        );
+        let children = vec![Packet::literal(1, 1 << 40); 2];
+        let packet = Packet::operator(0, Op::Product, children);
+        assert_eq!(
+            super::tree(&packet, None).lines().next(),
+            Some("product v0 = 1208925819614629174706176")
+        );
+
+        let packet = Packet::from_hex_str("9C0141080250320F1802104A08");
+        let tree = super::tree(&packet.unwrap(), None);
+        let values: Vec<_> = tree
+            .lines()
+            .map(|line| line.trim_start().split_once(" = ").unwrap())
+            .map(|(packet, value)| {
+                (packet.split(" v").next().unwrap(), value)
+            })
+            .collect();
+        assert_eq!(
+            values,
+            [
+                ("equal to", "1"),
+                ("sum", "4"),
+                ("literal", "1"),
+                ("literal", "3"),
+                ("product", "4"),
+                ("literal", "2"),
+                ("literal", "2"),
+            ]
+        );
+    }
+
+    #[test]
+    fn deep_tree() {
+        let depth = 10_000;
+        let mut packet = Packet::literal(0, 7);
+        for _ in 0..depth {
+            packet = Packet::operator(0, Op::Maximum, vec![packet]);
+        }
+        let tree = super::tree(&packet, None);
+        assert_eq!(tree.lines().count(), depth + 1);
+        assert!(tree.lines().all(|line| line.ends_with(" = 7")));
+    }
+}
@@ -1,20 +1,39 @@
-fn parse_input(input: &str) -> (i32, i32, i32, i32) {
-    let values: Vec<i32> = input.trim()[13..]
+use crate::{Error, Result};
+
+const DAY: u8 = 17;
+
+solution!(DAY, "Trick Shot");
+
+/// The target area, `x1..=x2` by `y1..=y2`.
+pub struct Input {
+    pub x1: i32,
+    pub x2: i32,
+    pub y1: i32,
+    pub y2: i32,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let input = input.trim();
+    let err = || Error::new(DAY, "invalid target area").at(1, input);
+    let values: Vec<i32> = input
+        .strip_prefix("target area: ")
+        .ok_or_else(err)?
         .split(", ")
-        .map(|s| {
-            s[2..]
-                .split("..")
-                .map(|v| v.parse().unwrap())
-                .collect::<Vec<_>>()
-        })
-        .flatten()
-        .collect();
+        .flat_map(|s| s.get(2..).unwrap_or_default().split(".."))
+        .map(|v| v.parse().map_err(|_| err()))
+        .collect::<Result<_>>()?;
 
-    (values[0], values[1], values[2], values[3])
+    match values[..] {
+        // the target has to be ahead of and below the probe's launcher
+        [x1, x2, y1, y2] if 0 < x1 && x1 <= x2 && y1 <= y2 && y2 < 0 => {
+            Ok(Input { x1, x2, y1, y2 })
+        }
+        _ => Err(err()),
+    }
 }
 
-pub fn part_one(input: &str) -> i32 {
-    let (_, _, y1, y2) = parse_input(input);
+pub fn part_one(input: &Input) -> Result<i32> {
+    let &Input { y1, y2, .. } = input;
     for velocity in y1..0 {
         let mut y = 0;
         let mut vy = -velocity;
@@ -25,12 +44,12 @@ pub fn part_one(input: &str) -> i32 {
         if y < y1 {
             continue;
         }
-        return velocity * (velocity - 1) / 2;
+        return Ok(velocity * (velocity - 1) / 2);
     }
-    unimplemented!()
+    Err(Error::new(DAY, "no velocity hits the target area"))
 }
 
-pub fn part_two(input: &str) -> i32 {
+pub fn part_two(input: &Input) -> Result<i32> {
     fn next(x: &mut i32, y: &mut i32, vx: &mut i32, vy: &mut i32) {
         *x += *vx;
         *y += *vy;
@@ -38,7 +57,7 @@ pub fn part_two(input: &str) -> i32 {
         *vy -= 1;
     }
 
-    let (x1, x2, y1, y2) = parse_input(input);
+    let &Input { x1, x2, y1, y2 } = input;
     let mut count = 0;
     for velocity_x in 0..=x2 {
         for velocity_y in y1..-y1 {
@@ -56,7 +75,11 @@ pub fn part_two(input: &str) -> i32 {
             }
         }
     }
-    count
+    Ok(count)
+}
+
+pub fn solve(input: &Input) -> Result<(i32, i32)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -66,13 +89,13 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(17);
-        assert_eq!(part_one(&input), 45);
+        let input = parse(&read_example(17)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 45);
     }
 
     #[test]
     fn example_two() {
-        let input = read_example(17);
-        assert_eq!(part_two(&input), 112);
+        let input = parse(&read_example(17)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 112);
     }
 }
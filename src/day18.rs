@@ -1,8 +1,16 @@
 use std::fmt::Display;
 use std::ops::Add;
 
-#[derive(Debug)]
-enum Node {
+use crate::{Error, Result};
+
+const DAY: u8 = 18;
+
+solution!(DAY, "Snailfish");
+
+/// A snailfish number: a regular number or a pair of numbers. `Empty`
+/// is the sum of no numbers, which adds up to the other one.
+#[derive(Debug, Clone)]
+pub enum Node {
     V(u32),
     P(Box<Node>, Box<Node>),
     Empty,
@@ -136,69 +144,89 @@ impl Add for Node {
     }
 }
 
-impl From<&str> for Node {
-    fn from(item: &str) -> Self {
+impl TryFrom<&str> for Node {
+    type Error = ();
+
+    fn try_from(item: &str) -> std::result::Result<Self, ()> {
         let mut stack: Vec<Node> = Vec::new();
         let mut iter = item.chars().peekable();
         while let Some(c) = iter.next() {
             match c {
                 '[' => {}
                 ']' => {
-                    let rhs = stack.pop().unwrap();
-                    let lhs = stack.pop().unwrap();
+                    let rhs = stack.pop().ok_or(())?;
+                    let lhs = stack.pop().ok_or(())?;
                     let node = Node::P(Box::new(lhs), Box::new(rhs));
                     stack.push(node);
                 }
                 ',' => {}
                 '0'..='9' => {
-                    let mut v = c.to_digit(10).unwrap();
-                    while let Some(c) = iter.peek() {
-                        if !c.is_digit(10) {
+                    let mut v = c as u32 - '0' as u32;
+                    while let Some(&c) = iter.peek() {
+                        if !c.is_ascii_digit() {
                             break;
                         }
-                        v = v * 10 + c.to_digit(10).unwrap();
+                        v = v * 10 + (c as u32 - '0' as u32);
                         iter.next();
                     }
                     let node = Node::V(v);
                     stack.push(node);
                 }
-                _ => {}
+                _ => return Err(()),
             }
         }
-        stack.pop().unwrap()
+        match (stack.pop(), stack.is_empty()) {
+            (Some(node), true) => Ok(node),
+            _ => Err(()),
+        }
     }
 }
 
-fn parse_input(input: &str) -> Vec<Node> {
-    input.lines().map(Node::from).collect()
+pub struct Input {
+    /// The numbers of the homework, a line each.
+    pub numbers: Vec<Node>,
 }
 
-pub fn part_one(input: &str) -> u32 {
-    let nodes = parse_input(input);
+pub fn parse(input: &str) -> Result<Input> {
+    let numbers = input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            Node::try_from(line).map_err(|_| {
+                Error::new(DAY, "invalid snailfish number").at(i + 1, line)
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok(Input { numbers })
+}
+
+pub fn part_one(input: &Input) -> Result<u32> {
     let mut sum = Node::Empty;
-    for node in nodes {
-        sum = sum + node;
+    for node in &input.numbers {
+        sum = sum + node.clone();
     }
-    sum.magnitude()
+    Ok(sum.magnitude())
 }
 
-pub fn part_two(input: &str) -> u32 {
+pub fn part_two(input: &Input) -> Result<u32> {
     let mut max = u32::MIN;
-    for (ia, a) in input.lines().enumerate() {
-        for (ib, b) in input.lines().enumerate() {
+    for (ia, a) in input.numbers.iter().enumerate() {
+        for (ib, b) in input.numbers.iter().enumerate() {
             if ia == ib {
                 continue;
             }
-            let a = Node::from(a);
-            let b = Node::from(b);
-            let c = a + b;
+            let c = a.clone() + b.clone();
             let m = c.magnitude();
             if m > max {
                 max = m;
             }
         }
     }
-    max
+    Ok(max)
+}
+
+pub fn solve(input: &Input) -> Result<(u32, u32)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -208,13 +236,13 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(18);
-        assert_eq!(part_one(&input), 4140);
+        let input = parse(&read_example(18)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 4140);
     }
 
     #[test]
     fn example_two() {
-        let input = read_example(18);
-        assert_eq!(part_two(&input), 3993);
+        let input = parse(&read_example(18)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 3993);
     }
 }
@@ -1,59 +1,32 @@
+pub mod rotation;
+
+use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::ops::Add;
 use std::ops::Neg;
 use std::ops::Sub;
 
+use crate::{Error, Result};
+pub use rotation::{Rotation, Transform};
+
+const DAY: u8 = 19;
+
+solution!(DAY, "Beacon Scanner");
+
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
-struct Point {
-    x: i32,
-    y: i32,
-    z: i32,
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
 }
 
 impl Point {
-    fn new(x: i32, y: i32, z: i32) -> Point {
+    pub fn new(x: i32, y: i32, z: i32) -> Point {
         Point { x, y, z }
     }
 
-    fn rotate(&self, orientation: u8) -> Point {
-        let (x, y, z) = (self.x, self.y, self.z);
-        match orientation {
-            0 => Point::new(x, y, z),
-            1 => Point::new(x, -z, y),
-            2 => Point::new(x, -y, -z),
-            3 => Point::new(x, z, -y),
-
-            4 => Point::new(-x, y, -z),
-            5 => Point::new(-x, -z, -y),
-            6 => Point::new(-x, -y, z),
-            7 => Point::new(-x, z, y),
-
-            8 => Point::new(y, x, -z),
-            9 => Point::new(y, -z, -x),
-            10 => Point::new(y, -x, z),
-            11 => Point::new(y, z, x),
-
-            12 => Point::new(-y, x, z),
-            13 => Point::new(-y, -z, x),
-            14 => Point::new(-y, -x, -z),
-            15 => Point::new(-y, z, -x),
-
-            16 => Point::new(z, y, -x),
-            17 => Point::new(z, x, y),
-            18 => Point::new(z, -y, x),
-            19 => Point::new(z, -x, -y),
-
-            20 => Point::new(-z, y, x),
-            21 => Point::new(-z, -x, y),
-            22 => Point::new(-z, -y, -x),
-            23 => Point::new(-z, x, -y),
-
-            _ => unimplemented!(),
-        }
-    }
-
-    fn manhattan_distance(&self, other: &Point) -> i32 {
+    pub fn manhattan_distance(&self, other: &Point) -> i32 {
         let x = (other.x - self.x).abs();
         let y = (other.y - self.y).abs();
         let z = (other.z - self.z).abs();
@@ -94,44 +67,30 @@ impl Neg for Point {
     }
 }
 
+/// The beacons a scanner detects, relative to its own position and
+/// orientation.
 #[derive(Debug, Clone)]
-struct Scanner {
-    position: Point,
-    beacons: HashSet<Point>,
+pub struct Scanner {
+    pub beacons: Vec<Point>,
 }
 
 impl Scanner {
-    fn new(position: Point, beacons: &[Point]) -> Scanner {
-        let beacons: HashSet<Point> = beacons.to_vec().into_iter().collect();
-        Scanner { position, beacons }
-    }
-
-    fn move_to(&self, position: Point) -> Scanner {
-        let delta = position - self.position;
-        let beacons: Vec<Point> =
-            self.beacons.iter().map(|p| *p + delta).collect();
-        Scanner::new(position, &beacons)
-    }
-
-    fn rotate(&self, orientation: u8) -> Scanner {
-        let beacons: Vec<Point> =
-            self.beacons.iter().map(|p| p.rotate(orientation)).collect();
-        Scanner::new(self.position, &beacons)
-    }
-
-    fn intersect(&self, other: &Scanner) -> usize {
-        self.beacons.intersection(&other.beacons).count()
-    }
-
-    fn find_match(&self, other: &Scanner) -> Option<Scanner> {
-        for beacon in self.beacons.iter() {
-            for orientation in 0..24 {
-                let rotated = other.rotate(orientation);
-                for rotated_beacon in &rotated.beacons {
-                    let p = self.position + *beacon - *rotated_beacon;
-                    let scanner = rotated.move_to(p);
-                    if self.intersect(&scanner) >= 12 {
-                        return Some(scanner);
+    /// Finds the transform that takes the coordinates of the other scanner
+    /// to those of this one, if they see at least 12 beacons in common.
+    fn find_match(&self, other: &Scanner) -> Option<Transform> {
+        let mut offsets: HashMap<Point, usize> = HashMap::new();
+        for rotation in Rotation::all() {
+            offsets.clear();
+            for b in &other.beacons {
+                let b = rotation * *b;
+                for a in &self.beacons {
+                    let count = offsets.entry(*a - b).or_insert(0);
+                    *count += 1;
+                    if *count >= 12 {
+                        return Some(Transform {
+                            rotation,
+                            translation: *a - b,
+                        });
                     }
                 }
             }
@@ -140,74 +99,92 @@ impl Scanner {
     }
 }
 
-fn parse_input(input: &str) -> Vec<Scanner> {
+pub struct Input {
+    /// The scanners in the order of the report, the first one being the
+    /// origin of the others.
+    pub scanners: Vec<Scanner>,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
     let mut scanners: Vec<Scanner> = Vec::new();
-    let mut iter = input.lines();
+    let mut iter = input.lines().enumerate();
 
-    fn parse_beacons(iter: &mut dyn Iterator<Item = &str>) -> Vec<Point> {
+    fn parse_beacons(
+        iter: &mut dyn Iterator<Item = (usize, &str)>,
+    ) -> Result<Vec<Point>> {
         let mut beacons: Vec<Point> = Vec::new();
-        for line in iter {
+        for (i, line) in iter {
             if line.is_empty() {
                 break;
             }
             let v = line
                 .split(',')
-                .map(|v| v.parse().unwrap())
-                .collect::<Vec<i32>>();
-            beacons.push(Point::new(v[0], v[1], v[2]));
+                .map(|v| v.parse())
+                .collect::<std::result::Result<Vec<i32>, _>>();
+            match v.as_deref() {
+                Ok(&[x, y, z]) => beacons.push(Point::new(x, y, z)),
+                _ => {
+                    let err = Error::new(DAY, "invalid beacon");
+                    return Err(err.at(i + 1, line));
+                }
+            }
         }
-        beacons
+        Ok(beacons)
     }
 
-    while let Some(line) = iter.next() {
+    while let Some((i, line)) = iter.next() {
         if line.starts_with("---") {
-            let beacons = parse_beacons(&mut iter);
-            let scanner = Scanner::new(Point::new(0, 0, 0), &beacons);
-            scanners.push(scanner);
+            let beacons = parse_beacons(&mut iter)?;
+            scanners.push(Scanner { beacons });
+        } else if !line.is_empty() {
+            let err = Error::new(DAY, "expected scanner header");
+            return Err(err.at(i + 1, line));
         }
     }
 
-    scanners
+    if scanners.is_empty() {
+        return Err(Error::new(DAY, "no scanners"));
+    }
+    Ok(Input { scanners })
 }
 
-pub fn part_one(input: &str) -> usize {
-    let scanners = parse_input(input);
-    let mut remaining: VecDeque<usize> = (1..scanners.len()).collect();
-    let mut base = scanners[0].clone();
-    while let Some(other) = remaining.pop_front() {
-        if let Some(scanner) = base.find_match(&scanners[other]) {
-            let beacons = base
-                .beacons
-                .union(&scanner.beacons)
-                .copied()
-                .collect::<Vec<Point>>(); //.map(|p| *p).collect();
-            base = Scanner::new(Point::new(0, 0, 0), &beacons);
-        } else {
-            remaining.push_back(other);
+/// Finds the transform of every scanner to the coordinates of the first
+/// one, chaining the transforms between overlapping scanners.
+pub fn align(input: &Input) -> Result<Vec<Transform>> {
+    let scanners = &input.scanners;
+    let mut transforms: Vec<Option<Transform>> = vec![None; scanners.len()];
+    transforms[0] = Some(Transform::IDENTITY);
+    let mut queue: VecDeque<usize> = VecDeque::from([0]);
+    while let Some(i) = queue.pop_front() {
+        let to_first = transforms[i].unwrap();
+        for j in 0..scanners.len() {
+            if transforms[j].is_some() {
+                continue;
+            }
+            if let Some(to_i) = scanners[i].find_match(&scanners[j]) {
+                transforms[j] = Some(to_i.then(&to_first));
+                queue.push_back(j);
+            }
         }
     }
-    base.beacons.len()
-}
-
-pub fn part_two(input: &str) -> i32 {
-    let scanners = parse_input(input);
-    let mut remaining: VecDeque<usize> = (1..scanners.len()).collect();
-    let mut base = scanners[0].clone();
-    let mut positions: Vec<Point> = Vec::new();
-    while let Some(other) = remaining.pop_front() {
-        if let Some(scanner) = base.find_match(&scanners[other]) {
-            let beacons = base
-                .beacons
-                .union(&scanner.beacons)
-                .copied()
-                .collect::<Vec<Point>>(); //.map(|p| *p).collect();
-            base = Scanner::new(Point::new(0, 0, 0), &beacons);
-            positions.push(scanner.position);
-        } else {
-            remaining.push_back(other);
+    match transforms.iter().position(Option::is_none) {
+        Some(j) => {
+            let msg = format!("scanner {} does not overlap", j);
+            Err(Error::new(DAY, msg))
         }
+        None => Ok(transforms.into_iter().flatten().collect()),
     }
+}
+
+/// Every beacon, in the coordinates of the first scanner.
+fn beacons(input: &Input, transforms: &[Transform]) -> HashSet<Point> {
+    let scanners = input.scanners.iter().zip(transforms);
+    scanners
+        .flat_map(|(s, t)| s.beacons.iter().map(move |&b| t.apply(b)))
+        .collect()
+}
 
+fn largest_distance(positions: &[Point]) -> i32 {
     let mut max = i32::MIN;
     for (i, a) in positions.iter().enumerate() {
         for b in positions[i + 1..].iter() {
@@ -220,6 +197,28 @@ pub fn part_two(input: &str) -> i32 {
     max
 }
 
+fn positions(transforms: &[Transform]) -> Vec<Point> {
+    transforms.iter().map(|t| t.translation).collect()
+}
+
+pub fn part_one(input: &Input) -> Result<usize> {
+    let transforms = align(input)?;
+    Ok(beacons(input, &transforms).len())
+}
+
+pub fn part_two(input: &Input) -> Result<i32> {
+    let transforms = align(input)?;
+    Ok(largest_distance(&positions(&transforms)))
+}
+
+pub fn solve(input: &Input) -> Result<(usize, i32)> {
+    let transforms = align(input)?;
+    Ok((
+        beacons(input, &transforms).len(),
+        largest_distance(&positions(&transforms)),
+    ))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -227,13 +226,36 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(19);
-        assert_eq!(part_one(&input), 79);
+        let input = parse(&read_example(19)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 79);
     }
 
     #[test]
     fn example_two() {
-        let input = read_example(19);
-        assert_eq!(part_two(&input), 3621);
+        let input = parse(&read_example(19)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 3621);
+        assert_eq!(solve(&input).unwrap(), (79, 3621));
+    }
+
+    #[test]
+    fn orientations() {
+        let input = parse(&read_example(19)).unwrap();
+        let transforms = align(&input).unwrap();
+        assert_eq!(transforms[1].translation, Point::new(68, -1246, -43));
+        assert_eq!(transforms[4].translation, Point::new(-20, -1133, 1061));
+        assert_eq!(transforms[1].rotation.to_string(), "(-x, y, -z)");
+        let beacon = Point::new(686, 422, 578);
+        assert_eq!(transforms[1].apply(beacon), Point::new(-618, -824, -621));
+
+        // scanner 4 overlaps scanner 1
+        let four_to_one = transforms[4].then(&transforms[1].inverse());
+        let one: HashSet<Point> =
+            input.scanners[1].beacons.iter().copied().collect();
+        let common = input.scanners[4]
+            .beacons
+            .iter()
+            .filter(|&&b| one.contains(&four_to_one.apply(b)))
+            .count();
+        assert!(common >= 12);
     }
 }
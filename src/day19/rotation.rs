@@ -0,0 +1,193 @@
+use std::fmt;
+use std::ops::Mul;
+
+use super::Point;
+
+/// A proper rotation of space by quarter turns: a 3x3 integer matrix
+/// whose rows each hold a single 1 or -1, with a determinant of 1.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct Rotation {
+    rows: [[i32; 3]; 3],
+}
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation {
+        rows: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+    };
+
+    /// The matrix, unless it is not a proper rotation.
+    pub fn from_rows(rows: [[i32; 3]; 3]) -> Option<Rotation> {
+        let rotation = Rotation { rows };
+        let is_orthogonal = rotation * rotation.inverse() == Self::IDENTITY;
+        match is_orthogonal && rotation.determinant() == 1 {
+            true => Some(rotation),
+            false => None,
+        }
+    }
+
+    pub fn rows(&self) -> [[i32; 3]; 3] {
+        self.rows
+    }
+
+    /// The 24 rotations, the identity first: 6 ways to pick the axis `x`
+    /// goes to, times 4 turns around it.
+    pub fn all() -> Vec<Rotation> {
+        let mut all = Vec::with_capacity(24);
+        for [a, b, c] in [
+            [0, 1, 2],
+            [0, 2, 1],
+            [1, 0, 2],
+            [1, 2, 0],
+            [2, 0, 1],
+            [2, 1, 0],
+        ] {
+            for signs in 0..8 {
+                let sign =
+                    |bit: i32| if signs >> bit & 1 == 0 { 1 } else { -1 };
+                let mut rows = [[0; 3]; 3];
+                rows[0][a] = sign(0);
+                rows[1][b] = sign(1);
+                rows[2][c] = sign(2);
+                all.extend(Rotation::from_rows(rows));
+            }
+        }
+        all
+    }
+
+    /// The transpose, as the matrix is orthogonal.
+    pub fn inverse(&self) -> Rotation {
+        let mut rows = [[0; 3]; 3];
+        for (i, row) in self.rows.iter().enumerate() {
+            for (j, &v) in row.iter().enumerate() {
+                rows[j][i] = v;
+            }
+        }
+        Rotation { rows }
+    }
+
+    fn determinant(&self) -> i32 {
+        let [a, b, c] = self.rows;
+        a[0] * (b[1] * c[2] - b[2] * c[1])
+            - a[1] * (b[0] * c[2] - b[2] * c[0])
+            + a[2] * (b[0] * c[1] - b[1] * c[0])
+    }
+}
+
+/// Composes two rotations, the one on the right first.
+impl Mul for Rotation {
+    type Output = Rotation;
+    fn mul(self, other: Rotation) -> Rotation {
+        let mut rows = [[0; 3]; 3];
+        for (i, row) in rows.iter_mut().enumerate() {
+            for (j, v) in row.iter_mut().enumerate() {
+                *v = (0..3).map(|k| self.rows[i][k] * other.rows[k][j]).sum();
+            }
+        }
+        Rotation { rows }
+    }
+}
+
+impl Mul<Point> for Rotation {
+    type Output = Point;
+    fn mul(self, p: Point) -> Point {
+        let [x, y, z] =
+            self.rows.map(|[a, b, c]| a * p.x + b * p.y + c * p.z);
+        Point::new(x, y, z)
+    }
+}
+
+/// Shows where the rotation takes each coordinate, e.g. `(x, -z, y)`.
+impl fmt::Display for Rotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let axes = self.rows.map(|row| {
+            let i = row.iter().position(|&v| v != 0).unwrap_or(0);
+            let sign = if row[i] < 0 { "-" } else { "" };
+            format!("{}{}", sign, ["x", "y", "z"][i])
+        });
+        write!(f, "({}, {}, {})", axes[0], axes[1], axes[2])
+    }
+}
+
+/// A rotation followed by a translation, taking the coordinates of a
+/// scanner to those of another.
+#[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
+pub struct Transform {
+    pub rotation: Rotation,
+    pub translation: Point,
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        rotation: Rotation::IDENTITY,
+        translation: Point { x: 0, y: 0, z: 0 },
+    };
+
+    pub fn apply(&self, p: Point) -> Point {
+        self.rotation * p + self.translation
+    }
+
+    /// This transform, then the next one.
+    pub fn then(&self, next: &Transform) -> Transform {
+        Transform {
+            rotation: next.rotation * self.rotation,
+            translation: next.apply(self.translation),
+        }
+    }
+
+    pub fn inverse(&self) -> Transform {
+        let rotation = self.rotation.inverse();
+        Transform {
+            rotation,
+            translation: -(rotation * self.translation),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn group() {
+        let all = Rotation::all();
+        assert_eq!(all.len(), 24);
+        assert_eq!(all[0], Rotation::IDENTITY);
+        for (i, &a) in all.iter().enumerate() {
+            assert!(!all[i + 1..].contains(&a));
+            assert_eq!(a * a.inverse(), Rotation::IDENTITY);
+            for &b in &all {
+                assert!(all.contains(&(a * b)));
+            }
+        }
+        assert_eq!(
+            Rotation::from_rows([[-1, 0, 0], [0, 1, 0], [0, 0, 1]]),
+            None
+        );
+        assert_eq!(
+            Rotation::from_rows([[1, 0, 0], [1, 0, 0], [0, 0, 1]]),
+            None
+        );
+    }
+
+    #[test]
+    fn points() {
+        let quarter = Rotation::from_rows([[1, 0, 0], [0, 0, -1], [0, 1, 0]]);
+        let quarter = quarter.unwrap();
+        assert_eq!(quarter.to_string(), "(x, -z, y)");
+        let p = Point::new(1, 2, 3);
+        assert_eq!(quarter * p, Point::new(1, -3, 2));
+        assert_eq!(quarter * quarter * quarter * quarter * p, p);
+
+        let a = Transform {
+            rotation: quarter,
+            translation: Point::new(10, 0, 0),
+        };
+        let b = Transform {
+            rotation: quarter.inverse(),
+            translation: Point::new(0, 5, 0),
+        };
+        assert_eq!(a.then(&b).apply(p), b.apply(a.apply(p)));
+        assert_eq!(a.inverse().apply(a.apply(p)), p);
+        assert_eq!(a.then(&a.inverse()), Transform::IDENTITY);
+    }
+}
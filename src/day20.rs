@@ -1,10 +1,20 @@
 use std::fmt::Display;
 
-struct Image {
-    width: usize,
-    height: usize,
-    data: Vec<Vec<u8>>,
-    default: u8,
+use crate::{Error, Result};
+
+const DAY: u8 = 20;
+
+solution!(DAY, "Trench Map");
+
+/// An image of light (1) and dark (0) pixels, surrounded by infinitely
+/// many pixels of the `default` value.
+#[derive(Clone)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// The pixels by row.
+    pub data: Vec<Vec<u8>>,
+    pub default: u8,
 }
 
 impl Image {
@@ -78,40 +88,77 @@ impl Display for Image {
     }
 }
 
-fn parse_input(input: &str) -> (Vec<u8>, Image) {
-    let mut iter = input.lines();
-    let enhancement: Vec<u8> = iter
-        .next()
-        .unwrap()
-        .chars()
-        .map(|c| if c == '#' { 1 } else { 0 })
-        .collect();
+fn parse_pixels(line: &str) -> Option<Vec<u8>> {
+    line.chars()
+        .map(|c| match c {
+            '#' => Some(1),
+            '.' => Some(0),
+            _ => None,
+        })
+        .collect()
+}
+
+pub struct Input {
+    /// The image enhancement algorithm, the output pixel of each of the
+    /// 512 neighbourhoods.
+    pub enhancement: Vec<u8>,
+    pub image: Image,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let mut iter = input.lines().enumerate();
+    let enhancement: Vec<u8> = match iter.next() {
+        Some((_, line)) => match parse_pixels(line) {
+            Some(enhancement) if enhancement.len() == 512 => enhancement,
+            _ => {
+                let err = Error::new(DAY, "invalid enhancement algorithm");
+                return Err(err.at(1, line));
+            }
+        },
+        None => return Err(Error::new(DAY, "missing enhancement algorithm")),
+    };
     iter.next();
     let data: Vec<Vec<u8>> = iter
-        .map(|line| {
-            line.chars().map(|c| if c == '#' { 1 } else { 0 }).collect()
+        .map(|(i, line)| match parse_pixels(line) {
+            Some(row) if !row.is_empty() => Ok(row),
+            _ => Err(Error::new(DAY, "invalid image row").at(i + 1, line)),
         })
-        .collect();
+        .collect::<Result<_>>()?;
+    if data.is_empty() || data.iter().any(|row| row.len() != data[0].len()) {
+        return Err(Error::new(DAY, "image is not rectangular"));
+    }
 
     let image = Image::new(data, 0);
 
-    (enhancement, image)
+    Ok(Input { enhancement, image })
 }
 
-pub fn part_one(input: &str) -> usize {
-    let (enhancement, mut image) = parse_input(input);
+pub fn part_one(input: &Input) -> Result<usize> {
+    let mut image = input.image.clone();
     for _ in 0..2 {
-        image = image.enhance(&enhancement);
+        image = image.enhance(&input.enhancement);
     }
-    image.lights()
+    Ok(image.lights())
 }
 
-pub fn part_two(input: &str) -> usize {
-    let (enhancement, mut image) = parse_input(input);
+pub fn part_two(input: &Input) -> Result<usize> {
+    let mut image = input.image.clone();
     for _ in 0..50 {
-        image = image.enhance(&enhancement);
+        image = image.enhance(&input.enhancement);
+    }
+    Ok(image.lights())
+}
+
+pub fn solve(input: &Input) -> Result<(usize, usize)> {
+    let mut image = input.image.clone();
+    for _ in 0..2 {
+        image = image.enhance(&input.enhancement);
+    }
+    let lights = image.lights();
+    for _ in 2..50 {
+        image = image.enhance(&input.enhancement);
     }
-    image.lights()
+    Ok((lights, image.lights()))
 }
 
 #[cfg(test)]
@@ -121,13 +168,13 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(20);
-        assert_eq!(part_one(&input), 35);
+        let input = parse(&read_example(20)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 35);
     }
 
     #[test]
     fn example_two() {
-        let input = read_example(20);
-        assert_eq!(part_two(&input), 3351);
+        let input = parse(&read_example(20)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 3351);
     }
 }
@@ -1,15 +1,39 @@
 use std::collections::HashMap;
 
-fn parse_input(input: &str) -> [u32; 2] {
+use crate::{Error, Result};
+
+const DAY: u8 = 21;
+
+solution!(DAY, "Dirac Dice");
+
+pub struct Input {
+    pub positions: [u32; 2],
+}
+
+pub fn parse(input: &str) -> Result<Input> {
     let positions: Vec<u32> = input
         .lines()
-        .map(|line| line.chars().last().unwrap().to_digit(10).unwrap())
-        .collect();
-    [positions[0], positions[1]]
+        .enumerate()
+        .map(|(i, line)| {
+            match line.rsplit_once(": ").map(|(_, v)| v.parse()) {
+                Some(Ok(position)) if (1..=10).contains(&position) => {
+                    Ok(position)
+                }
+                _ => {
+                    let err = Error::new(DAY, "invalid starting position");
+                    Err(err.at(i + 1, line))
+                }
+            }
+        })
+        .collect::<Result<_>>()?;
+    match positions[..] {
+        [a, b] => Ok(Input { positions: [a, b] }),
+        _ => Err(Error::new(DAY, "expected two players")),
+    }
 }
 
-pub fn part_one(input: &str) -> u32 {
-    let mut positions = parse_input(input);
+pub fn part_one(input: &Input) -> Result<u32> {
+    let mut positions = input.positions;
     let mut scores: [u32; 2] = [0, 0];
     let mut player = 0;
     let mut times = 0;
@@ -25,18 +49,18 @@ pub fn part_one(input: &str) -> u32 {
 
         player = (player + 1) % 2;
         if score >= 1000 {
-            break times * 3 * scores[player];
+            break Ok(times * 3 * scores[player]);
         }
     }
 }
 
-pub fn part_two(input: &str) -> u64 {
+pub fn part_two(input: &Input) -> Result<u64> {
     type Status = [(u32, u32); 2]; // [(player 1 position, score), (player 2 position, score)]
     type CacheKey = (usize, Status); // (player, status)
     type Winners = [u64; 2]; // [count of player 1 win, count of player 2 win]
     type Cache = HashMap<CacheKey, Winners>;
 
-    let positions = parse_input(input);
+    let positions = input.positions;
     let mut cache: Cache = HashMap::new();
 
     fn play(cache: &mut Cache, player: usize, status: Status) -> Winners {
@@ -70,7 +94,11 @@ pub fn part_two(input: &str) -> u64 {
     }
 
     let winners = play(&mut cache, 0, [(positions[0], 0), (positions[1], 0)]);
-    *winners.iter().max().unwrap()
+    Ok(winners[0].max(winners[1]))
+}
+
+pub fn solve(input: &Input) -> Result<(u32, u64)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -80,13 +108,13 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(21);
-        assert_eq!(part_one(&input), 739785);
+        let input = parse(&read_example(21)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 739785);
     }
 
     #[test]
     fn example_two() {
-        let input = read_example(21);
-        assert_eq!(part_two(&input), 444356092776315);
+        let input = parse(&read_example(21)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 444356092776315);
     }
 }
@@ -1,8 +1,15 @@
+use crate::{Error, Result};
+
+const DAY: u8 = 22;
+
+solution!(DAY, "Reactor Reboot");
+
+/// The coordinates `a..=b`, `size` of them.
 #[derive(Debug, Copy, Clone)]
-struct Segment {
-    a: i64,
-    b: i64,
-    size: i64,
+pub struct Segment {
+    pub a: i64,
+    pub b: i64,
+    pub size: i64,
 }
 
 impl Segment {
@@ -23,12 +30,13 @@ impl Segment {
     }
 }
 
+/// A cuboid of cubes, `size` of them.
 #[derive(Debug, Copy, Clone)]
-struct Cube {
-    x: Segment,
-    y: Segment,
-    z: Segment,
-    size: i64,
+pub struct Cube {
+    pub x: Segment,
+    pub y: Segment,
+    pub z: Segment,
+    pub size: i64,
 }
 
 impl Cube {
@@ -48,7 +56,8 @@ impl Cube {
     }
 }
 
-type Instruction = (bool, Cube);
+/// Whether to turn a cuboid on or off.
+pub type Instruction = (bool, Cube);
 
 #[derive(Debug)]
 struct Reactor {
@@ -86,31 +95,54 @@ impl Reactor {
     }
 }
 
-fn parse_input(input: &str) -> Vec<Instruction> {
-    input
+pub struct Input {
+    /// The reboot steps, in order.
+    pub steps: Vec<Instruction>,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
+    let steps = input
         .lines()
-        .map(|line| {
-            let (operator, line) = line.split_once(' ').unwrap();
-            let operator = operator == "on";
-            let v: Vec<i64> = line
+        .enumerate()
+        .map(|(i, line)| {
+            let err =
+                || Error::new(DAY, "invalid reboot step").at(i + 1, line);
+            let (operator, cuboid) = line.split_once(' ').ok_or_else(err)?;
+            let operator = match operator {
+                "on" => true,
+                "off" => false,
+                _ => return Err(err()),
+            };
+            let v: Vec<i64> = cuboid
                 .split(',')
-                .map(|s| s.split('=').nth(1).unwrap())
+                .map(|s| s.split('=').nth(1).ok_or_else(err))
+                .collect::<Result<Vec<&str>>>()?
+                .into_iter()
                 .flat_map(|s| s.split(".."))
-                .map(|s| s.parse().unwrap())
-                .collect();
-            let x = Segment::new(v[0], v[1]);
-            let y = Segment::new(v[2], v[3]);
-            let z = Segment::new(v[4], v[5]);
+                .map(|s| s.parse().map_err(|_| err()))
+                .collect::<Result<_>>()?;
+            let (x, y, z) = match v[..] {
+                [x1, x2, y1, y2, z1, z2]
+                    if x1 <= x2 && y1 <= y2 && z1 <= z2 =>
+                {
+                    (
+                        Segment::new(x1, x2),
+                        Segment::new(y1, y2),
+                        Segment::new(z1, z2),
+                    )
+                }
+                _ => return Err(err()),
+            };
             let operand = Cube::new(x, y, z);
-            (operator, operand)
+            Ok((operator, operand))
         })
-        .collect()
+        .collect::<Result<_>>()?;
+    Ok(Input { steps })
 }
 
-pub fn part_one(input: &str) -> i64 {
-    let steps = parse_input(input);
+pub fn part_one(input: &Input) -> Result<i64> {
     let mut reactor = Reactor::new();
-    for (operator, operand) in steps {
+    for &(operator, operand) in &input.steps {
         if operand.x.a < -50 || operand.x.b > 50 {
             continue;
         }
@@ -122,16 +154,19 @@ pub fn part_one(input: &str) -> i64 {
         }
         reactor.turn(operator, operand);
     }
-    reactor.size()
+    Ok(reactor.size())
 }
 
-pub fn part_two(input: &str) -> i64 {
-    let steps = parse_input(input);
+pub fn part_two(input: &Input) -> Result<i64> {
     let mut reactor = Reactor::new();
-    for (operator, operand) in steps {
+    for &(operator, operand) in &input.steps {
         reactor.turn(operator, operand);
     }
-    reactor.size()
+    Ok(reactor.size())
+}
+
+pub fn solve(input: &Input) -> Result<(i64, i64)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -141,13 +176,13 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(22);
-        assert_eq!(part_one(&input), 590784);
+        let input = parse(&read_example(22)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 590784);
     }
 
     #[test]
     fn example_two() {
-        let input = read_as_string(22, "example2");
-        assert_eq!(part_two(&input), 2758514936282235);
+        let input = parse(&read_as_string(22, "example2").unwrap()).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 2758514936282235);
     }
 }
@@ -1,16 +1,29 @@
+mod astar;
+pub mod burrow;
+mod game;
+mod plan;
+
 use std::cmp::Ordering;
 use std::collections::BinaryHeap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 
-const COSTS: [usize; 5] = [0, 1, 10, 100, 1000];
+use crate::{Error, Result};
+pub use astar::astar;
+pub use burrow::{energy, Burrow};
+pub use game::Game;
+pub use plan::{Move, Plan};
+
+const DAY: u8 = 23;
+
+solution!(DAY, "Amphipod");
 
 #[derive(Debug, Clone, Eq, PartialEq)]
 struct State {
     cost: usize,
-    burrow: Vec<u8>,
-    #[cfg(debug_assertions)]
-    log: Vec<Vec<u8>>,
+    cells: Vec<u8>,
+    /// The burrow before the last move, and the move.
+    last: Option<(Vec<u8>, Move)>,
 }
 
 impl Ord for State {
@@ -25,264 +38,177 @@ impl PartialOrd for State {
     }
 }
 
-impl State {
-    #[cfg(debug_assertions)]
-    fn new(cost: usize, burrow: Vec<u8>, log: Vec<Vec<u8>>) -> State {
-        State { cost, burrow, log }
-    }
-
-    #[cfg(not(debug_assertions))]
-    fn new(cost: usize, burrow: Vec<u8>) -> State {
-        State { cost, burrow }
-    }
-
-    fn is_final(&self) -> bool {
-        for (i, &v) in self.burrow[11..].iter().enumerate() {
-            if v as usize != (i % 4) + 1 {
-                return false;
+/// The moves amphipods may make, as the cells they move from and to,
+/// some of them blocked by others on the way.
+fn next_movement(burrow: &Burrow, cells: &[u8]) -> Vec<(usize, usize)> {
+    let mut movement: Vec<(usize, usize)> = Vec::new();
+
+    // the cell an amphipod of each species would move to in its room,
+    // if the room holds no other species
+    let homes: Vec<Option<usize>> = (0..burrow.rooms())
+        .map(|room| {
+            let species = room as u8 + 1;
+            for floor in (0..burrow.depth()).rev() {
+                let cell = burrow.cell(room, floor);
+                match cells[cell] {
+                    0 => return Some(cell),
+                    s if s == species => {}
+                    _ => return None,
+                }
             }
+            None
+        })
+        .collect();
+
+    // the topmost amphipod of each room
+    let mut tops: Vec<Option<usize>> = vec![None; burrow.rooms()];
+    for (cell, &v) in cells.iter().enumerate().skip(burrow.hallway()) {
+        let (room, _) = burrow.room_of(cell).unwrap();
+        if v > 0 && tops[room].is_none() {
+            tops[room] = Some(cell);
         }
-        true
     }
 
-    fn next_movement(&self) -> Vec<(usize, usize)> {
-        let mut movement: Vec<(usize, usize)> = Vec::new();
-
-        let amphipods: Vec<(usize, u8)> = self
-            .burrow
-            .iter()
-            .enumerate()
-            .filter(|(_, &v)| v > 0)
-            .map(|(i, &v)| (i, v))
-            .collect();
-
-        let depth = (self.burrow.len() - 11) / 4;
-
-        // move from hallway to room
-        for &(spot, v) in amphipods.iter().filter(|(spot, _)| *spot < 11) {
-            for room in 1..=4 {
-                if v as usize != room {
-                    // that room is not its destination room
-                    continue;
-                }
-                for floor in (0..depth).rev() {
-                    let target = 10 + room + floor * 4;
-                    let v = self.burrow[target];
-                    if v == 0 {
-                        movement.push((spot, target));
-                        break;
-                    }
-                    if v != room as u8 {
-                        // that room contains amphipod which do not have that room
-                        // as their own destination
-                        break;
-                    }
-                }
-            }
+    // move from hallway to room
+    for (spot, &v) in cells[..burrow.hallway()].iter().enumerate() {
+        if v > 0 {
+            movement.extend(homes[v as usize - 1].map(|t| (spot, t)));
         }
+    }
 
-        // move from room to hallway
-        let mut rooms: [Option<usize>; 4] = [None; 4];
-        for &(spot, _) in amphipods.iter().filter(|(spot, _)| *spot >= 11) {
-            let i = (spot - 11) % 4;
-            if rooms[i].is_none() || rooms[i].unwrap() > spot {
-                rooms[i] = Some(spot);
-            }
+    // move from room to hallway, or straight to its own room
+    for (room, &spot) in tops.iter().enumerate() {
+        let spot = match spot {
+            Some(spot) => spot,
+            None => continue,
+        };
+        let home = cells[spot] as usize - 1;
+        if home != room {
+            movement.extend(homes[home].map(|t| (spot, t)));
         }
-        for &spot in rooms.iter().flatten() {
-            for target in [0, 1, 3, 5, 7, 9, 10] {
+        for target in 0..burrow.hallway() {
+            if burrow.is_stop(target) {
                 movement.push((spot, target));
             }
         }
-
-        movement
     }
+
+    movement
 }
 
 type PathCache = HashMap<(usize, usize), Vec<usize>>;
 
-fn build_path_cache(depth: usize) -> PathCache {
-    let mut cache: PathCache = HashMap::new();
-
-    fn path(a: usize, b: usize) -> Vec<usize> {
-        if a == b {
-            return vec![a];
-        }
-        if a >= 11 && b >= 11 && (a - 11) % 4 == (b - 11) % 4 {
-            let mut spots = vec![a];
-            if a < b {
-                spots.append(&mut path(a + 4, b));
-            } else {
-                spots.append(&mut path(a - 4, b));
+/// The moves not blocked by other amphipods.
+fn moves(burrow: &Burrow, cache: &PathCache, cells: &[u8]) -> Vec<Move> {
+    next_movement(burrow, cells)
+        .into_iter()
+        .filter_map(|(from, to)| {
+            let path = &cache[&(from, to)];
+            if path[1..].iter().any(|&i| cells[i] != 0) {
+                return None;
             }
-            return spots;
-        }
-        if a >= 11 {
-            let n = if a >= 15 { a - 4 } else { (a - 11) % 4 * 2 + 2 };
-            let mut spots = vec![a];
-            spots.append(&mut path(n, b));
-            return spots;
-        }
-        if b >= 11 {
-            let n = if b >= 15 { b - 4 } else { (b - 11) % 4 * 2 + 2 };
-            let mut spots = path(a, n);
-            spots.push(b);
-            return spots;
-        }
-        if a < b {
-            (a..=b).collect()
-        } else {
-            (b..=a).rev().collect()
-        }
-    }
+            Some(Move {
+                amphipod: cells[from],
+                from,
+                to,
+                energy: energy(cells[from]) * (path.len() - 1),
+            })
+        })
+        .collect()
+}
 
-    let len = depth * 4 + 11;
-    for a in 0..len - 1 {
-        for b in a..len {
-            if cache.contains_key(&(a, b)) {
-                continue;
+fn build_path_cache(burrow: &Burrow) -> PathCache {
+    let mut cache: PathCache = HashMap::new();
+    let len = burrow.cells().len();
+    for a in 0..len {
+        for b in 0..len {
+            if a != b {
+                cache.insert((a, b), burrow.path(a, b));
             }
-            let mut path = path(a, b);
-            cache.insert((a, b), path.clone());
-            path.reverse();
-            cache.insert((b, a), path);
         }
     }
-
     cache
 }
 
-fn search(initial: Vec<u8>, cache: PathCache) -> usize {
+/// Finds the moves that sort the amphipods with the least energy with
+/// Dijkstra's algorithm, unless they cannot be sorted.
+pub fn dijkstra(burrow: &Burrow) -> Option<Plan> {
+    let cache = build_path_cache(burrow);
+    let mut parents: HashMap<Vec<u8>, (Vec<u8>, Move)> = HashMap::new();
     let mut visited: HashSet<Vec<u8>> = HashSet::new();
     let mut heap: BinaryHeap<State> = BinaryHeap::new();
 
-    #[cfg(not(debug_assertions))]
-    let state = State::new(0, initial);
-    #[cfg(debug_assertions)]
-    let state = State::new(0, initial, Vec::new());
-
-    heap.push(state);
+    heap.push(State {
+        cost: 0,
+        cells: burrow.cells().to_vec(),
+        last: None,
+    });
 
     while let Some(state) = heap.pop() {
-        if state.is_final() {
-            #[cfg(debug_assertions)]
-            {
-                for s in state.log {
-                    print_burrow(&s);
-                }
-                print_burrow(&state.burrow);
-            }
-
-            return state.cost;
-        }
-        if visited.contains(&state.burrow) {
+        if visited.contains(&state.cells) {
             continue;
         }
-        visited.insert(state.burrow.clone());
-        for (from, to) in state.next_movement() {
-            let mut burrow = state.burrow.clone();
-            let amphipod = burrow[from];
-            burrow[to] = amphipod;
-            burrow[from] = 0;
-            if visited.contains(&burrow) {
-                continue;
-            }
-
-            let path = cache.get(&(from, to)).unwrap();
-            let mut is_blocked = false;
-            for i in &path[1..] {
-                if state.burrow[*i] != 0 {
-                    is_blocked = true;
-                    break;
-                }
-            }
-            if is_blocked {
+        visited.insert(state.cells.clone());
+        if let Some(last) = state.last {
+            parents.insert(state.cells.clone(), last);
+        }
+        if burrow.is_sorted(&state.cells) {
+            return Some(Plan::trace(state.cost, &state.cells, &parents));
+        }
+        for m in moves(burrow, &cache, &state.cells) {
+            let mut cells = state.cells.clone();
+            m.apply(&mut cells);
+            if visited.contains(&cells) {
                 continue;
             }
-
-            let distance = path.len() - 1;
-            let cost = COSTS[amphipod as usize] * distance + state.cost;
-            #[cfg(not(debug_assertions))]
-            heap.push(State::new(cost, burrow));
-
-            #[cfg(debug_assertions)]
-            {
-                let mut log = state.log.clone();
-                log.push(state.burrow.clone());
-                heap.push(State::new(cost, burrow, log));
-            }
+            heap.push(State {
+                cost: state.cost + m.energy,
+                cells,
+                last: Some((state.cells.clone(), m)),
+            });
         }
     }
-    0
+    None
 }
 
-#[cfg(debug_assertions)]
-fn print_burrow(burrow: &[u8]) {
-    const SYMBOLS: [char; 5] = ['.', 'A', 'B', 'C', 'D'];
+pub struct Input {
+    pub burrow: Burrow,
+}
 
-    // hallway
-    println!("+-----------------------+");
-    print!("|");
-    for v in &burrow[..11] {
-        print!(" {}", SYMBOLS[*v as usize]);
-    }
-    println!(" |");
-
-    // rooms
-    let mut iter = burrow[11..].iter().peekable();
-    macro_rules! print_room {
-        ($fmt:literal) => {
-            let v1 = SYMBOLS[*iter.next().unwrap() as usize];
-            let v2 = SYMBOLS[*iter.next().unwrap() as usize];
-            let v3 = SYMBOLS[*iter.next().unwrap() as usize];
-            let v4 = SYMBOLS[*iter.next().unwrap() as usize];
-            println!($fmt, v1, v2, v3, v4);
-        };
-    }
-    print_room!("+---+ {} | {} | {} | {} +---+");
-    while iter.peek().is_some() {
-        print_room!("    | {} | {} | {} | {} |");
-    }
-    println!("    +---+---+---+---+");
-    println!();
+pub fn parse(input: &str) -> Result<Input> {
+    let burrow = Burrow::parse(input)?;
+    Ok(Input { burrow })
 }
 
-fn parse_input(input: &str) -> Vec<u8> {
-    input
-        .lines()
-        .flat_map(|s| {
-            s.chars().map(|c| match c {
-                '.' => Some(0),
-                'A' => Some(1),
-                'B' => Some(2),
-                'C' => Some(3),
-                'D' => Some(4),
-                _ => None,
-            })
-        })
-        .flatten()
-        .collect()
+fn least_energy(burrow: &Burrow) -> Result<usize> {
+    match astar(burrow) {
+        Some(plan) => Ok(plan.energy),
+        None => Err(Error::new(DAY, "the amphipods cannot be sorted")),
+    }
 }
 
-pub fn part_one(input: &str) -> usize {
-    let burrow = parse_input(input);
-    let cache = build_path_cache(2);
-    search(burrow, cache)
+pub fn part_one(input: &Input) -> Result<usize> {
+    least_energy(&input.burrow)
 }
 
-pub fn part_two(input: &str) -> usize {
-    let origin = parse_input(input);
-    let extend = parse_input(
+/// Inserts the two floors folded in the diagram.
+pub fn unfold(burrow: &Burrow) -> Result<Burrow> {
+    if burrow.rooms() != 4 {
+        return Err(Error::new(DAY, "the folded diagram has four rooms"));
+    }
+    burrow.insert_floors(
+        1,
         "#D#C#B#A#
          #D#B#A#C#",
-    );
-    let mut burrow = Vec::new();
-    burrow.extend_from_slice(&origin[..11]);
-    burrow.extend_from_slice(&origin[11..15]);
-    burrow.extend_from_slice(&extend[..]);
-    burrow.extend_from_slice(&origin[15..]);
-    let cache = build_path_cache(4);
-    search(burrow, cache)
+    )
+}
+
+pub fn part_two(input: &Input) -> Result<usize> {
+    least_energy(&unfold(&input.burrow)?)
+}
+
+pub fn solve(input: &Input) -> Result<(usize, usize)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -292,13 +218,90 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(23);
-        assert_eq!(part_one(&input), 12521);
+        let input = parse(&read_example(23)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 12521);
     }
 
     #[test]
     fn example_two() {
-        let input = read_example(23);
-        assert_eq!(part_two(&input), 44169);
+        let input = parse(&read_example(23)).unwrap();
+        assert_eq!(part_two(&input).unwrap(), 44169);
+    }
+
+    #[test]
+    fn other_shapes() {
+        let input = parse(
+            "\
+###############
+#.............#
+###B#A#C#D#E###
+  ###########",
+        )
+        .unwrap();
+        assert_eq!(part_one(&input).unwrap(), 46);
+        assert!(part_two(&input).is_err());
+
+        let input = parse(
+            "\
+#########
+#A......#
+###.#B###
+  #A#B#
+  #A#B#
+  #####",
+        )
+        .unwrap();
+        assert_eq!(part_one(&input).unwrap(), 3);
+    }
+
+    #[test]
+    fn unsolvable() {
+        // B and A wait in the hallway, each in the way of the other
+        let input = parse(
+            "\
+###########
+#...B.A...#
+###.###.###
+  #A###B#
+  #######",
+        )
+        .unwrap();
+        assert!(astar(&input.burrow).is_none());
+        assert!(dijkstra(&input.burrow).is_none());
+        let err = part_one(&input).unwrap_err();
+        assert_eq!(err.message, "the amphipods cannot be sorted");
+
+        let input = parse("#####\n#...#\n###A###\n  ###").unwrap();
+        assert_eq!(part_one(&input).unwrap(), 0);
+    }
+
+    #[test]
+    fn plans() {
+        let input = parse(&read_example(23)).unwrap();
+        let burrow = &input.burrow;
+        for plan in [astar(burrow), dijkstra(burrow)] {
+            let plan = plan.unwrap();
+            assert_eq!(plan.energy, 12521);
+            let moves = plan.moves.iter().map(|m| m.energy);
+            assert_eq!(moves.sum::<usize>(), plan.energy);
+            let burrows = plan.burrows(burrow);
+            assert_eq!(burrows.len(), plan.moves.len() + 1);
+            assert!(burrows[..plan.moves.len()]
+                .iter()
+                .all(|cells| !burrow.is_sorted(cells)));
+            assert!(burrow.is_sorted(&burrows[plan.moves.len()]));
+        }
+
+        let plan = astar(burrow).unwrap();
+        let rendered = plan.render(burrow);
+        assert!(rendered.starts_with(&burrow.render(burrow.cells())));
+        assert!(rendered.ends_with("  #########\n12521 energy\n"));
+        let m = Move {
+            amphipod: 2,
+            from: 13,
+            to: 3,
+            energy: 40,
+        };
+        assert_eq!(m.to_string(), "B 13 -> 3, 40 energy");
     }
 }
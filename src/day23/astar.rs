@@ -0,0 +1,154 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap};
+
+use super::burrow::{energy, Burrow};
+use super::plan::{Move, Plan};
+use super::{build_path_cache, dijkstra, moves};
+
+/// Finds the moves that sort the amphipods with the least energy with A*,
+/// unless they cannot be sorted. The burrows are packed in a `u128`, and
+/// the larger ones are left to Dijkstra's algorithm.
+pub fn astar(burrow: &Burrow) -> Option<Plan> {
+    let bits = bits_per_cell(burrow);
+    let len = burrow.cells().len();
+    if len * bits > u128::BITS as usize {
+        return dijkstra(burrow);
+    }
+    let cache = build_path_cache(burrow);
+    let mut best: HashMap<u128, usize> = HashMap::new();
+    let mut parents: HashMap<u128, (u128, Move)> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+
+    let initial = pack(burrow.cells(), bits);
+    best.insert(initial, 0);
+    heap.push(Reverse((heuristic(burrow, burrow.cells()), 0, initial)));
+
+    while let Some(Reverse((_, cost, key))) = heap.pop() {
+        if best[&key] < cost {
+            continue;
+        }
+        let mut cells = unpack(key, len, bits);
+        if burrow.is_sorted(&cells) {
+            return Some(Plan::trace(cost, &key, &parents));
+        }
+        for m in moves(burrow, &cache, &cells) {
+            let (from, to) = (m.from, m.to);
+            let amphipod = m.amphipod as u128;
+            let next =
+                key ^ amphipod << (from * bits) ^ amphipod << (to * bits);
+            let cost = cost + m.energy;
+            match best.entry(next) {
+                Entry::Occupied(e) if *e.get() <= cost => continue,
+                Entry::Occupied(mut e) => *e.get_mut() = cost,
+                Entry::Vacant(e) => {
+                    e.insert(cost);
+                }
+            }
+            parents.insert(next, (key, m));
+            cells.swap(from, to);
+            let estimate = cost + heuristic(burrow, &cells);
+            cells.swap(from, to);
+            heap.push(Reverse((estimate, cost, next)));
+        }
+    }
+    None
+}
+
+/// The least energy to send every amphipod home if none ever blocked
+/// another: up out of its room, along the hallway, and down its own room
+/// as deep as the amphipods going in before it leave room for.
+fn heuristic(burrow: &Burrow, cells: &[u8]) -> usize {
+    let entrances = burrow.entrances();
+    let mut entering = vec![0; burrow.rooms()];
+    let mut estimate = 0;
+    for (cell, &s) in cells.iter().enumerate() {
+        if s == 0 {
+            continue;
+        }
+        let home = s as usize - 1;
+        let steps = match burrow.room_of(cell) {
+            None => cell.abs_diff(entrances[home]) + 1,
+            Some((room, floor)) if room == home => {
+                let mut below = floor + 1..burrow.depth();
+                if below.all(|f| cells[burrow.cell(room, f)] == s) {
+                    continue;
+                }
+                // out of the way and back
+                floor + 1 + 2 + 1
+            }
+            Some((room, floor)) => {
+                floor + 1 + entrances[room].abs_diff(entrances[home]) + 1
+            }
+        };
+        estimate += energy(s) * steps;
+        entering[home] += 1;
+    }
+    for (room, &n) in entering.iter().enumerate() {
+        estimate += energy(room as u8 + 1) * n * (n.max(1) - 1) / 2;
+    }
+    estimate
+}
+
+/// The bits it takes to store an amphipod of any species, or none.
+fn bits_per_cell(burrow: &Burrow) -> usize {
+    (usize::BITS - burrow.rooms().leading_zeros()) as usize
+}
+
+fn pack(cells: &[u8], bits: usize) -> u128 {
+    cells
+        .iter()
+        .rev()
+        .fold(0, |key, &s| key << bits | s as u128)
+}
+
+fn unpack(key: u128, len: usize, bits: usize) -> Vec<u8> {
+    let mask = (1 << bits) - 1;
+    (0..len).map(|i| (key >> (i * bits) & mask) as u8).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::unfold;
+    use super::*;
+    use crate::read_example;
+
+    #[test]
+    fn packing() {
+        let burrow = Burrow::parse(&read_example(23)).unwrap();
+        let burrow = unfold(&burrow).unwrap();
+        let bits = bits_per_cell(&burrow);
+        assert_eq!(bits, 3);
+        let key = pack(burrow.cells(), bits);
+        assert_eq!(unpack(key, burrow.cells().len(), bits), burrow.cells());
+    }
+
+    #[test]
+    fn lower_bound() {
+        let burrow = Burrow::parse(&read_example(23)).unwrap();
+        let estimate = heuristic(&burrow, burrow.cells());
+        assert!(0 < estimate && estimate <= 12521);
+        let mut cells = burrow.cells().to_vec();
+        cells[11..].copy_from_slice(&[1, 2, 3, 4, 1, 2, 3, 4]);
+        assert_eq!(heuristic(&burrow, &cells), 0);
+
+        let burrow = unfold(&burrow).unwrap();
+        let estimate = heuristic(&burrow, burrow.cells());
+        assert!(0 < estimate && estimate <= 44169);
+    }
+
+    #[test]
+    fn same_as_dijkstra() {
+        for diagram in [
+            "#############\n#.A.......B.#\n###.#.#C#D###\n  \
+               #A#B#C#D#\n  #########",
+            "#########\n#.......#\n###B#A###\n  #A#B#\n  #####",
+            "###############\n#.............#\n###B#A#C#D#E###\n  \
+               ###########",
+        ] {
+            let burrow = Burrow::parse(diagram).unwrap();
+            let (a, d) = (astar(&burrow), dijkstra(&burrow));
+            assert_eq!(a.unwrap().energy, d.unwrap().energy, "{}", diagram);
+        }
+    }
+}
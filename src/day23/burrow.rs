@@ -0,0 +1,432 @@
+use super::DAY;
+use crate::{Error, Result};
+
+/// The most rooms a burrow can have, so that the energy of every species
+/// and the sums of it fit in a `usize` with room to spare.
+const MAX_ROOMS: usize = 10;
+
+/// The cells of a burrow, and the amphipods in them.
+///
+/// The cells are numbered along the hallway from the left, then room by
+/// room from the left on the top floor, then on each floor below. An
+/// amphipod is its species from 1, for `A`, and an empty cell is 0. The
+/// home of species `s` is room `s - 1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Burrow {
+    hallway: usize,
+    entrances: Vec<usize>,
+    depth: usize,
+    cells: Vec<u8>,
+}
+
+impl Burrow {
+    /// Parses a diagram such as:
+    ///
+    /// ```text
+    /// #############
+    /// #...........#
+    /// ###B#C#B#D###
+    ///   #A#D#C#A#
+    ///   #########
+    /// ```
+    ///
+    /// The first line with open cells is the hallway, and the lines below
+    /// it with open cells are the floors of the rooms, which must all be
+    /// below the hallway and line up. There can be at most 10 rooms, and
+    /// every room must be as deep as there are amphipods of its species.
+    pub fn parse(diagram: &str) -> Result<Burrow> {
+        for (i, line) in diagram.lines().enumerate() {
+            if let Some(c) = line.chars().find(|&c| !is_tile(c)) {
+                let msg = format!("unknown character {:?}", c);
+                return Err(Error::new(DAY, msg).at(i + 1, line));
+            }
+        }
+        let mut lines = diagram
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i + 1, line, open_columns(line)))
+            .skip_while(|(_, _, columns)| columns.is_empty());
+        let (_, hallway_line, columns) = lines
+            .next()
+            .ok_or_else(|| Error::new(DAY, "no hallway in the diagram"))?;
+        let left = columns[0];
+        let hallway = columns.len();
+        if columns.iter().enumerate().any(|(i, &c)| c != left + i) {
+            return Err(Error::new(DAY, "the hallway has walls in it"));
+        }
+        let mut cells = parse_cells(hallway_line, &columns);
+
+        let mut entrances: Vec<usize> = Vec::new();
+        let mut depth = 0;
+        for (i, line, columns) in lines {
+            if columns.is_empty() {
+                break;
+            }
+            let err = |msg| Error::new(DAY, msg).at(i, line);
+            if depth == 0 {
+                if columns.iter().any(|&c| c < left || c >= left + hallway) {
+                    return Err(err("a room is not below the hallway"));
+                }
+                entrances = columns.iter().map(|&c| c - left).collect();
+            } else if entrances
+                .iter()
+                .map(|&e| e + left)
+                .ne(columns.iter().copied())
+            {
+                return Err(err("the rooms do not line up"));
+            }
+            cells.extend(parse_cells(line, &columns));
+            depth += 1;
+        }
+        if depth == 0 {
+            return Err(Error::new(DAY, "no rooms below the hallway"));
+        }
+        let burrow = Burrow {
+            hallway,
+            entrances,
+            depth,
+            cells,
+        };
+        burrow.check_species()?;
+        Ok(burrow)
+    }
+
+    fn check_species(&self) -> Result<()> {
+        if self.rooms() > MAX_ROOMS {
+            let msg = format!(
+                "{} rooms, more than the {} there can be",
+                self.rooms(),
+                MAX_ROOMS
+            );
+            return Err(Error::new(DAY, msg));
+        }
+        if let Some(&s) =
+            self.cells.iter().find(|&&s| s as usize > self.rooms())
+        {
+            let msg = format!("no room for amphipod {}", letter(s));
+            return Err(Error::new(DAY, msg));
+        }
+        for s in 1..=self.rooms() as u8 {
+            let n = self.cells.iter().filter(|&&c| c == s).count();
+            if n != self.depth {
+                let msg = format!(
+                    "{} amphipods {} for rooms {} deep",
+                    n,
+                    letter(s),
+                    self.depth
+                );
+                return Err(Error::new(DAY, msg));
+            }
+        }
+        Ok(())
+    }
+
+    /// Inserts floors below the `top` first floors of every room, from
+    /// lines holding an amphipod per room such as `#D#C#B#A#`.
+    pub fn insert_floors(&self, top: usize, lines: &str) -> Result<Burrow> {
+        let mut floors = Vec::new();
+        for (i, line) in lines.lines().enumerate() {
+            let floor = parse_cells(line, &open_columns(line));
+            if floor.len() != self.rooms() || floor.contains(&0) {
+                let msg = format!("expected {} amphipods", self.rooms());
+                return Err(Error::new(DAY, msg).at(i + 1, line));
+            }
+            floors.extend(floor);
+        }
+        let at = self.cell(0, top.min(self.depth));
+        let mut cells = self.cells.clone();
+        cells.splice(at..at, floors);
+        let burrow = Burrow {
+            depth: (cells.len() - self.hallway) / self.rooms(),
+            cells,
+            ..self.clone()
+        };
+        burrow.check_species()?;
+        Ok(burrow)
+    }
+
+    /// The same burrow with the amphipods elsewhere.
+    pub fn with_cells(&self, cells: Vec<u8>) -> Burrow {
+        assert_eq!(cells.len(), self.cells.len());
+        Burrow {
+            cells,
+            ..self.clone()
+        }
+    }
+
+    /// Where a cell is, in words: `hallway 4`, or `room B, floor 1`,
+    /// counted from 1 at the left and at the top.
+    pub fn place(&self, cell: usize) -> String {
+        match self.room_of(cell) {
+            Some((room, floor)) => {
+                let room = letter(room as u8 + 1);
+                format!("room {}, floor {}", room, floor + 1)
+            }
+            None => format!("hallway {}", cell + 1),
+        }
+    }
+
+    /// The number of cells of the hallway.
+    pub fn hallway(&self) -> usize {
+        self.hallway
+    }
+
+    pub fn rooms(&self) -> usize {
+        self.entrances.len()
+    }
+
+    /// The number of cells of every room.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The hallway cell above each room.
+    pub fn entrances(&self) -> &[usize] {
+        &self.entrances
+    }
+
+    /// The amphipods at the start.
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    /// The cell of a room on a floor, 0 being the top one.
+    pub fn cell(&self, room: usize, floor: usize) -> usize {
+        self.hallway + floor * self.rooms() + room
+    }
+
+    /// The room and the floor of a cell, unless it is in the hallway.
+    pub fn room_of(&self, cell: usize) -> Option<(usize, usize)> {
+        let i = cell.checked_sub(self.hallway)?;
+        Some((i % self.rooms(), i / self.rooms()))
+    }
+
+    /// Whether amphipods may stop at the cell of the hallway, which they
+    /// may not right above a room.
+    pub fn is_stop(&self, cell: usize) -> bool {
+        cell < self.hallway && !self.entrances.contains(&cell)
+    }
+
+    /// The cells from one to the other, both included.
+    pub fn path(&self, from: usize, to: usize) -> Vec<usize> {
+        let (up, start) = self.to_hallway(from);
+        let (mut down, end) = self.to_hallway(to);
+        if up.last().is_some() && up.last() == down.last() {
+            // within a room
+            let i = up.iter().position(|&c| c == to);
+            let j = down.iter().position(|&c| c == from);
+            return match (i, j) {
+                (Some(i), _) => up[..=i].to_vec(),
+                (_, Some(j)) => down[..=j].iter().rev().copied().collect(),
+                _ => unreachable!(),
+            };
+        }
+        let mut path = up;
+        if start <= end {
+            path.extend(start..=end);
+        } else {
+            path.extend((end..=start).rev());
+        }
+        down.reverse();
+        path.extend(down);
+        path
+    }
+
+    /// The cells from a cell up to the top floor of its room, if it is in
+    /// a room, and the hallway cell it then gets to.
+    fn to_hallway(&self, cell: usize) -> (Vec<usize>, usize) {
+        match self.room_of(cell) {
+            Some((room, floor)) => {
+                let up = (0..=floor).rev().map(|f| self.cell(room, f));
+                (up.collect(), self.entrances[room])
+            }
+            None => (Vec::new(), cell),
+        }
+    }
+
+    /// Whether every amphipod is home.
+    pub fn is_sorted(&self, cells: &[u8]) -> bool {
+        cells[self.hallway..]
+            .iter()
+            .enumerate()
+            .all(|(i, &s)| s as usize == i % self.rooms() + 1)
+    }
+
+    /// Draws the burrow with other amphipods in it, in the format of the
+    /// diagrams.
+    pub fn render(&self, cells: &[u8]) -> String {
+        let width = self.hallway + 2;
+        let first = self.entrances[0];
+        let last = self.entrances[self.rooms() - 1];
+        let mut s = String::new();
+        s.push_str(&"#".repeat(width));
+        s.push_str("\n#");
+        s.extend(cells[..self.hallway].iter().map(|&c| letter(c)));
+        s.push_str("#\n");
+        for floor in 0..self.depth {
+            let mut line: Vec<char> = match floor {
+                0 => vec!['#'; width],
+                _ => {
+                    let mut line = vec![' '; last + 3];
+                    line[first..].fill('#');
+                    line
+                }
+            };
+            for (room, &entrance) in self.entrances.iter().enumerate() {
+                line[entrance + 1] = letter(cells[self.cell(room, floor)]);
+            }
+            s.extend(line);
+            s.push('\n');
+        }
+        s.push_str(&" ".repeat(first));
+        s.push_str(&"#".repeat(last - first + 3));
+        s.push('\n');
+        s
+    }
+}
+
+/// The energy it takes an amphipod of a species to move a step.
+pub fn energy(species: u8) -> usize {
+    10usize.pow(species as u32 - 1)
+}
+
+pub fn letter(species: u8) -> char {
+    match species {
+        0 => '.',
+        s => (b'A' + s - 1) as char,
+    }
+}
+
+fn is_tile(c: char) -> bool {
+    matches!(c, '#' | ' ' | '.' | 'A'..='Z')
+}
+
+fn open_columns(line: &str) -> Vec<usize> {
+    line.bytes()
+        .enumerate()
+        .filter(|(_, c)| *c == b'.' || c.is_ascii_uppercase())
+        .map(|(i, _)| i)
+        .collect()
+}
+
+fn parse_cells(line: &str, columns: &[usize]) -> Vec<u8> {
+    let line = line.as_bytes();
+    columns
+        .iter()
+        .map(|&i| match line[i] {
+            b'.' => 0,
+            c => c - b'A' + 1,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_example;
+
+    #[test]
+    fn geometry() {
+        let burrow = Burrow::parse(&read_example(23)).unwrap();
+        assert_eq!(burrow.hallway(), 11);
+        assert_eq!(burrow.entrances(), [2, 4, 6, 8]);
+        assert_eq!(burrow.depth(), 2);
+        assert_eq!(burrow.cells()[11..], [2, 3, 2, 4, 1, 4, 3, 1]);
+        assert_eq!(burrow.room_of(16), Some((1, 1)));
+        assert_eq!(burrow.room_of(10), None);
+        assert!(burrow.is_stop(3) && !burrow.is_stop(4));
+        assert_eq!(burrow.place(3), "hallway 4");
+        assert_eq!(burrow.place(16), "room B, floor 2");
+
+        assert_eq!(burrow.path(16, 11), [16, 12, 4, 3, 2, 11]);
+        assert_eq!(burrow.path(0, 15), [0, 1, 2, 11, 15]);
+        assert_eq!(burrow.path(10, 8), [10, 9, 8]);
+        assert_eq!(burrow.path(15, 11), [15, 11]);
+        assert_eq!(burrow.path(12, 16), [12, 16]);
+    }
+
+    #[test]
+    fn render() {
+        let diagram = read_example(23);
+        let burrow = Burrow::parse(&diagram).unwrap();
+        assert_eq!(burrow.render(burrow.cells()), diagram);
+
+        let diagram = "\
+###############
+#A............#
+###.#B#C#D#E###
+  #A#B#C#D#E#
+  #A#B#C#D#E#
+  ###########
+";
+        let burrow = Burrow::parse(diagram).unwrap();
+        assert_eq!((burrow.rooms(), burrow.depth()), (5, 3));
+        assert_eq!(burrow.render(burrow.cells()), diagram);
+        assert!(!burrow.is_sorted(burrow.cells()));
+        let mut cells = burrow.cells().to_vec();
+        cells.swap(0, burrow.cell(0, 0));
+        assert!(burrow.is_sorted(&cells));
+    }
+
+    #[test]
+    fn insert_floors() {
+        let burrow = Burrow::parse(&read_example(23)).unwrap();
+        let unfolded = burrow.insert_floors(1, "#D#C#B#A#\n#D#B#A#C#");
+        let unfolded = unfolded.unwrap();
+        assert_eq!(unfolded.depth(), 4);
+        assert_eq!(
+            unfolded.render(unfolded.cells()).lines().nth(3),
+            Some("  #D#C#B#A#")
+        );
+        assert!(burrow.insert_floors(1, "#D#C#B#").is_err());
+        assert!(burrow.insert_floors(1, "#A#A#B#B#").is_err());
+    }
+
+    #[test]
+    fn invalid_diagrams() {
+        for diagram in [
+            "",
+            "#####\n#...#\n#####",
+            "#####\n#.#.#\n##.##\n#####",
+            "#####\n#...#\n##.##\n#.###\n#####",
+            "#######\n#.....#\n###C#.##\n  #.#.#\n  #####",
+        ] {
+            assert!(Burrow::parse(diagram).is_err(), "{}", diagram);
+        }
+
+        let err = |diagram| Burrow::parse(diagram).unwrap_err().to_string();
+        assert_eq!(
+            err("#######\n#.....#\n###a#B###\n  #####"),
+            "day 23, line 3: unknown character 'a': \"###a#B###\""
+        );
+        assert_eq!(
+            err("#######\n#A....#\n###A#B###\n  #####"),
+            "day 23: 2 amphipods A for rooms 1 deep"
+        );
+        assert_eq!(
+            err("#######\n#.....#\n###A#C###\n  #####"),
+            "day 23: no room for amphipod C"
+        );
+        let rooms: Vec<u8> = (1..=MAX_ROOMS as u8 + 1).collect();
+        let floor = |rooms: &[u8]| {
+            let letters: Vec<String> =
+                rooms.iter().map(|&s| letter(s).to_string()).collect();
+            letters.join("#")
+        };
+        let mut shifted = rooms.clone();
+        shifted.rotate_left(1);
+        let width = rooms.len() * 2 + 3;
+        let diagram = format!(
+            "{}\n#{}#\n###{}###\n  #{}#\n  {}",
+            "#".repeat(width),
+            ".".repeat(width - 2),
+            floor(&shifted),
+            floor(&rooms),
+            "#".repeat(width - 4)
+        );
+        assert_eq!(
+            err(&diagram),
+            "day 23: 11 rooms, more than the 10 there can be"
+        );
+    }
+}
@@ -0,0 +1,111 @@
+use super::astar::astar;
+use super::burrow::Burrow;
+use super::plan::{Move, Plan};
+use super::{build_path_cache, moves, PathCache};
+
+/// A burrow sorted by hand, one move at a time.
+pub struct Game {
+    burrow: Burrow,
+    cache: PathCache,
+    cells: Vec<u8>,
+    played: Vec<Move>,
+}
+
+impl Game {
+    pub fn new(burrow: Burrow) -> Game {
+        Game {
+            cache: build_path_cache(&burrow),
+            cells: burrow.cells().to_vec(),
+            burrow,
+            played: Vec::new(),
+        }
+    }
+
+    pub fn burrow(&self) -> &Burrow {
+        &self.burrow
+    }
+
+    /// The amphipods where the moves played left them.
+    pub fn cells(&self) -> &[u8] {
+        &self.cells
+    }
+
+    pub fn played(&self) -> &[Move] {
+        &self.played
+    }
+
+    /// The energy the moves played took.
+    pub fn energy(&self) -> usize {
+        self.played.iter().map(|m| m.energy).sum()
+    }
+
+    pub fn is_sorted(&self) -> bool {
+        self.burrow.is_sorted(&self.cells)
+    }
+
+    /// The moves the amphipods may make from here.
+    pub fn moves(&self) -> Vec<Move> {
+        moves(&self.burrow, &self.cache, &self.cells)
+    }
+
+    /// Plays a move, unless it is not one of the legal moves.
+    pub fn play(&mut self, m: Move) -> bool {
+        if !self.moves().contains(&m) {
+            return false;
+        }
+        m.apply(&mut self.cells);
+        self.played.push(m);
+        true
+    }
+
+    /// Takes the last move back.
+    pub fn undo(&mut self) -> Option<Move> {
+        let m = self.played.pop()?;
+        self.cells[m.from] = m.amphipod;
+        self.cells[m.to] = 0;
+        Some(m)
+    }
+
+    /// The moves that sort the amphipods from here with the least energy,
+    /// unless they cannot be sorted any more.
+    pub fn best(&self) -> Option<Plan> {
+        astar(&self.burrow.with_cells(self.cells.clone()))
+    }
+
+    pub fn render(&self) -> String {
+        self.burrow.render(&self.cells)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::read_example;
+
+    #[test]
+    fn play() {
+        let burrow = Burrow::parse(&read_example(23)).unwrap();
+        let mut game = Game::new(burrow);
+        // every amphipod on the top floor may go to 7 hallway cells
+        assert_eq!(game.moves().len(), 4 * 7);
+        let best = game.best().unwrap();
+        assert_eq!(best.energy, 12521);
+
+        let m = best.moves[0];
+        assert!(game.play(m));
+        assert!(!game.play(m));
+        assert_eq!(game.energy(), m.energy);
+        assert_eq!(game.best().unwrap().energy, 12521 - m.energy);
+
+        assert_eq!(game.undo(), Some(m));
+        assert_eq!(game.cells(), game.burrow().cells());
+        assert_eq!(game.undo(), None);
+
+        for &m in &best.moves {
+            assert!(game.play(m), "{}", m);
+        }
+        assert!(game.is_sorted());
+        assert_eq!(game.energy(), 12521);
+        assert!(game.moves().iter().all(|m| game.burrow().is_stop(m.to)));
+    }
+}
@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+
+use super::burrow::{letter, Burrow};
+
+/// An amphipod moving from a cell to another, with the energy it takes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub amphipod: u8,
+    pub from: usize,
+    pub to: usize,
+    pub energy: usize,
+}
+
+impl Move {
+    pub fn apply(&self, cells: &mut [u8]) {
+        cells[self.from] = 0;
+        cells[self.to] = self.amphipod;
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} {} -> {}, {} energy",
+            letter(self.amphipod),
+            self.from,
+            self.to,
+            self.energy
+        )
+    }
+}
+
+/// The moves that sort the amphipods, and the energy they take.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub energy: usize,
+    pub moves: Vec<Move>,
+}
+
+impl Plan {
+    /// Follows the moves that led to `last` back to the start.
+    pub(super) fn trace<K: Hash + Eq>(
+        energy: usize,
+        last: &K,
+        parents: &HashMap<K, (K, Move)>,
+    ) -> Plan {
+        let mut moves = Vec::new();
+        let mut key = last;
+        while let Some((parent, m)) = parents.get(key) {
+            moves.push(*m);
+            key = parent;
+        }
+        moves.reverse();
+        Plan { energy, moves }
+    }
+
+    /// The burrow before every move, and once sorted.
+    pub fn burrows(&self, burrow: &Burrow) -> Vec<Vec<u8>> {
+        let mut cells = burrow.cells().to_vec();
+        let mut burrows = vec![cells.clone()];
+        for m in &self.moves {
+            m.apply(&mut cells);
+            burrows.push(cells.clone());
+        }
+        burrows
+    }
+
+    /// Draws the burrow before every move, followed by the move.
+    pub fn render(&self, burrow: &Burrow) -> String {
+        let mut s = String::new();
+        let burrows = self.burrows(burrow);
+        for (cells, m) in burrows.iter().zip(&self.moves) {
+            s.push_str(&burrow.render(cells));
+            s.push_str(&format!("{}\n\n", m));
+        }
+        s.push_str(&burrow.render(&burrows[burrows.len() - 1]));
+        s.push_str(&format!("{} energy\n", self.energy));
+        s
+    }
+}
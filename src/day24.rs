@@ -1,115 +1,79 @@
-use std::collections::HashSet;
+pub mod alu;
+pub mod compile;
+pub mod models;
+pub mod monad;
+mod optimize;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
-struct Regs(i64, i64, i64, i64);
+use crate::{Error, Result};
+pub use alu::{parse_program, Alu, Instruction, Operand, Register, Regs};
+pub use compile::Program;
+pub use models::{count, validate, ModelNumbers, Order};
+use monad::Monad;
 
-impl Regs {
-    fn set(&mut self, n: &str, v: i64) {
-        match n {
-            "w" => self.0 = v,
-            "x" => self.1 = v,
-            "y" => self.2 = v,
-            "z" => self.3 = v,
-            _ => unreachable!(),
-        }
-    }
+const DAY: u8 = 24;
 
-    fn get(&self, n: &str) -> i64 {
-        match n {
-            "w" => self.0,
-            "x" => self.1,
-            "y" => self.2,
-            "z" => self.3,
-            _ => unreachable!(),
-        }
-    }
+solution!(DAY, "Arithmetic Logic Unit");
+
+pub struct Input {
+    pub program: Vec<Instruction>,
 }
 
-fn search(program: Vec<&str>, digits: Vec<u8>) -> u64 {
-    struct Env<'a> {
-        program: Vec<&'a str>,
-        digits: Vec<u8>,
-        visited: HashSet<(usize, Regs)>,
-    }
+pub fn parse(input: &str) -> Result<Input> {
+    let program = parse_program(input)?;
+    Ok(Input { program })
+}
 
-    fn exec(env: &mut Env, pc: usize, regs: Regs) -> Option<Vec<u8>> {
-        if env.visited.contains(&(pc, regs)) {
-            return None;
-        }
-        env.visited.insert((pc, regs));
-        if regs.3 > 1_000_000 {
-            return None;
-        }
-        if pc >= env.program.len() {
-            if regs.3 == 0 {
-                return Some(Vec::with_capacity(14));
-            }
-            return None;
+pub fn part_one(input: &Input) -> Result<u64> {
+    let number = match Monad::analyze(&input.program) {
+        Some(monad) => monad.largest(),
+        None => {
+            let program = Program::optimize(&input.program, &[Register::Z]);
+            ModelNumbers::new(&program, Order::Descending).next()
         }
+    };
+    number.ok_or_else(|| Error::new(DAY, "no valid model number"))
+}
 
-        let mut new_regs = regs;
-        let inst = env.program[pc];
-        let op = &inst[..3];
-        let a = &inst[4..5];
-        if op == "inp" {
-            for d in env.digits.clone() {
-                new_regs.set(a, d as i64);
-                if let Some(mut rv) = exec(env, pc + 1, new_regs) {
-                    rv.push(d);
-                    return Some(rv);
-                }
-            }
-            return None;
+pub fn part_two(input: &Input) -> Result<u64> {
+    let number = match Monad::analyze(&input.program) {
+        Some(monad) => monad.smallest(),
+        None => {
+            let program = Program::optimize(&input.program, &[Register::Z]);
+            ModelNumbers::new(&program, Order::Ascending).next()
         }
-        let b = &inst[6..];
-        let av = regs.get(a);
-        let bv = match b.parse::<i64>() {
-            Ok(v) => v,
-            _ => regs.get(b),
-        };
-        let v = match op {
-            "add" => av + bv,
-            "mul" => av * bv,
-            "div" => av / bv,
-            "mod" => av % bv,
-            "eql" => {
-                if av == bv {
-                    1
-                } else {
-                    0
-                }
-            }
-            _ => unreachable!(),
-        };
-        new_regs.set(a, v);
-        exec(env, pc + 1, new_regs)
-    }
-
-    let mut env = Env {
-        program,
-        digits,
-        visited: HashSet::new(),
     };
-
-    exec(&mut env, 0, Regs(0, 0, 0, 0))
-        .unwrap()
-        .iter()
-        .rev()
-        .fold(0, |acc, &x| acc * 10 + x as u64)
+    number.ok_or_else(|| Error::new(DAY, "no valid model number"))
 }
 
-fn parse_input(input: &str) -> Vec<&str> {
-    input.lines().collect()
+pub fn solve(input: &Input) -> Result<(u64, u64)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
-pub fn part_one(input: &str) -> u64 {
-    let program = parse_input(input);
-    let digits: Vec<u8> = vec![9, 8, 7, 6, 5, 4, 3, 2, 1];
-    search(program, digits)
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fallback() {
+        // valid when the second digit is the first plus one
+        let mut program =
+            "inp z\ninp x\nadd z 1\neql z x\nadd z -1\n".to_string();
+        program.push_str(&"inp w\n".repeat(12));
+        let input = parse(&program).unwrap();
+        assert!(Monad::analyze(&input.program).is_none());
+        assert_eq!(part_one(&input).unwrap(), 89_999_999_999_999);
+        assert_eq!(part_two(&input).unwrap(), 12_111_111_111_111);
+    }
 
-pub fn part_two(input: &str) -> u64 {
-    let program = parse_input(input);
-    let digits: Vec<u8> = vec![1, 2, 3, 4, 5, 6, 7, 8, 9];
-    search(program, digits)
+    #[test]
+    fn fallback_without_z_limit() {
+        // valid when the first digit is 9, through a z of 9 million
+        let mut program = "inp z\nmul z 1000000\n".to_string();
+        program.push_str(&"inp w\n".repeat(13));
+        program.push_str("div z 1000000\neql z 9\neql z 0");
+        let input = parse(&program).unwrap();
+        assert!(validate(&input.program, &[9; 14]).unwrap());
+        assert_eq!(part_one(&input).unwrap(), 99_999_999_999_999);
+        assert_eq!(part_two(&input).unwrap(), 91_111_111_111_111);
+    }
 }
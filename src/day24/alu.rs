@@ -0,0 +1,370 @@
+use std::fmt;
+
+use super::DAY;
+use crate::{Error, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+impl Register {
+    pub fn from_name(name: &str) -> Option<Register> {
+        match name {
+            "w" => Some(Register::W),
+            "x" => Some(Register::X),
+            "y" => Some(Register::Y),
+            "z" => Some(Register::Z),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Register::W => "w",
+            Register::X => "x",
+            Register::Y => "y",
+            Register::Z => "z",
+        }
+    }
+}
+
+/// The second operand of an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Register(Register),
+    Number(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Instruction {
+    Inp(Register),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    Div(Register, Operand),
+    Mod(Register, Operand),
+    Eql(Register, Operand),
+}
+
+impl Instruction {
+    /// The register the instruction writes.
+    pub fn target(&self) -> Register {
+        match *self {
+            Instruction::Inp(a) => a,
+            Instruction::Add(a, _)
+            | Instruction::Mul(a, _)
+            | Instruction::Div(a, _)
+            | Instruction::Mod(a, _)
+            | Instruction::Eql(a, _) => a,
+        }
+    }
+
+    /// The second operand, unless the instruction is `inp`.
+    pub fn operand(&self) -> Option<Operand> {
+        match *self {
+            Instruction::Inp(_) => None,
+            Instruction::Add(_, b)
+            | Instruction::Mul(_, b)
+            | Instruction::Div(_, b)
+            | Instruction::Mod(_, b)
+            | Instruction::Eql(_, b) => Some(b),
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Instruction::Inp(_) => "inp",
+            Instruction::Add(..) => "add",
+            Instruction::Mul(..) => "mul",
+            Instruction::Div(..) => "div",
+            Instruction::Mod(..) => "mod",
+            Instruction::Eql(..) => "eql",
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.name(), self.target().name())?;
+        match self.operand() {
+            Some(Operand::Register(b)) => write!(f, " {}", b.name()),
+            Some(Operand::Number(b)) => write!(f, " {}", b),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Parses a program, an instruction per line.
+pub fn parse_program(input: &str) -> Result<Vec<Instruction>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            parse_instruction(line)
+                .map_err(|msg| Error::new(DAY, msg).at(i + 1, line))
+        })
+        .collect()
+}
+
+fn parse_instruction(line: &str) -> std::result::Result<Instruction, &str> {
+    let args: Vec<&str> = line.split_whitespace().collect();
+    let (name, a, b) = match args[..] {
+        [name, a] => (name, a, None),
+        [name, a, b] => (name, a, Some(b)),
+        _ => return Err("invalid instruction"),
+    };
+    let a = Register::from_name(a).ok_or("invalid register")?;
+    let b = match b.map(|b| (b, Register::from_name(b))) {
+        Some((_, Some(b))) => Some(Operand::Register(b)),
+        Some((b, None)) => {
+            let n = b.parse().map_err(|_| "invalid operand")?;
+            Some(Operand::Number(n))
+        }
+        None => None,
+    };
+    let inst = match (name, b) {
+        ("inp", None) => Instruction::Inp(a),
+        ("add", Some(b)) => Instruction::Add(a, b),
+        ("mul", Some(b)) => Instruction::Mul(a, b),
+        ("div", Some(b)) => Instruction::Div(a, b),
+        ("mod", Some(b)) => Instruction::Mod(a, b),
+        ("eql", Some(b)) => Instruction::Eql(a, b),
+        ("inp" | "add" | "mul" | "div" | "mod" | "eql", _) => {
+            return Err("wrong number of operands")
+        }
+        _ => return Err("unknown instruction"),
+    };
+    Ok(inst)
+}
+
+/// Why the ALU crashes on an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Crash {
+    DivisionByZero,
+    /// `mod a b` with `a < 0` or `b <= 0`.
+    InvalidModulo,
+    /// `inp` once every input digit is read.
+    EndOfInput,
+    /// A result that does not fit in 64 bits.
+    Overflow,
+}
+
+impl fmt::Display for Crash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Crash::DivisionByZero => "division by zero",
+            Crash::InvalidModulo => {
+                "modulo of a negative number, or by zero or less"
+            }
+            Crash::EndOfInput => "no input left",
+            Crash::Overflow => "overflow",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Regs {
+    pub w: i64,
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Regs {
+    pub fn get(&self, r: Register) -> i64 {
+        match r {
+            Register::W => self.w,
+            Register::X => self.x,
+            Register::Y => self.y,
+            Register::Z => self.z,
+        }
+    }
+
+    pub fn set(&mut self, r: Register, v: i64) {
+        match r {
+            Register::W => self.w = v,
+            Register::X => self.x = v,
+            Register::Y => self.y = v,
+            Register::Z => self.z = v,
+        }
+    }
+
+    /// Executes an instruction, with `input` the next digit for `inp`.
+    pub fn execute(
+        &mut self,
+        inst: &Instruction,
+        input: Option<i64>,
+    ) -> std::result::Result<(), Crash> {
+        let a = inst.target();
+        let av = self.get(a);
+        let bv = match inst.operand() {
+            Some(Operand::Register(b)) => self.get(b),
+            Some(Operand::Number(b)) => b,
+            None => 0,
+        };
+        let v = match inst {
+            Instruction::Inp(_) => input.ok_or(Crash::EndOfInput)?,
+            Instruction::Add(..) => {
+                av.checked_add(bv).ok_or(Crash::Overflow)?
+            }
+            Instruction::Mul(..) => {
+                av.checked_mul(bv).ok_or(Crash::Overflow)?
+            }
+            Instruction::Div(..) if bv == 0 => {
+                return Err(Crash::DivisionByZero)
+            }
+            Instruction::Div(..) => {
+                av.checked_div(bv).ok_or(Crash::Overflow)?
+            }
+            Instruction::Mod(..) if av < 0 || bv <= 0 => {
+                return Err(Crash::InvalidModulo)
+            }
+            Instruction::Mod(..) => av % bv,
+            Instruction::Eql(..) => (av == bv) as i64,
+        };
+        self.set(a, v);
+        Ok(())
+    }
+}
+
+/// A crash of the ALU, on the instruction at `pc`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AluError {
+    pub pc: usize,
+    pub instruction: Instruction,
+    pub crash: Crash,
+}
+
+impl fmt::Display for AluError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} on line {}: {}",
+            self.crash,
+            self.pc + 1,
+            self.instruction
+        )
+    }
+}
+
+impl std::error::Error for AluError {}
+
+impl From<AluError> for Error {
+    fn from(err: AluError) -> Error {
+        let text = err.instruction.to_string();
+        Error::new(DAY, err.crash.to_string()).at(err.pc + 1, &text)
+    }
+}
+
+/// Runs programs on an ALU.
+pub struct Alu<'a> {
+    program: &'a [Instruction],
+}
+
+impl<'a> Alu<'a> {
+    pub fn new(program: &'a [Instruction]) -> Alu<'a> {
+        Alu { program }
+    }
+
+    /// Runs the program from zeroed registers, reading `inp` from `input`,
+    /// and returns the registers at the end.
+    pub fn run(
+        &self,
+        input: impl IntoIterator<Item = i64>,
+    ) -> std::result::Result<Regs, AluError> {
+        let mut input = input.into_iter();
+        let mut regs = Regs::default();
+        for (pc, inst) in self.program.iter().enumerate() {
+            let digit = match inst {
+                Instruction::Inp(_) => input.next(),
+                _ => None,
+            };
+            regs.execute(inst, digit).map_err(|crash| AluError {
+                pc,
+                instruction: *inst,
+                crash,
+            })?;
+        }
+        Ok(regs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parser() {
+        let program = parse_program("inp w\nadd z w\nmod z -2").unwrap();
+        assert_eq!(
+            program,
+            [
+                Instruction::Inp(Register::W),
+                Instruction::Add(Register::Z, Operand::Register(Register::W)),
+                Instruction::Mod(Register::Z, Operand::Number(-2)),
+            ]
+        );
+        assert_eq!(program[2].to_string(), "mod z -2");
+
+        for (text, message) in [
+            ("inp w\nsub x 1", "unknown instruction"),
+            ("inp w\nadd a 1", "invalid register"),
+            ("inp w\nadd x 1.5", "invalid operand"),
+            ("inp w\ninp w 1", "wrong number of operands"),
+            ("inp w\n", "invalid instruction"),
+        ] {
+            let err = parse_program(&format!("{}\n", text)).unwrap_err();
+            assert_eq!(err.message, message, "{}", text);
+            assert_eq!(err.line, Some(2), "{}", text);
+        }
+    }
+
+    #[test]
+    fn interpreter() {
+        let program = parse_program(
+            "inp w\nadd z w\nmod z 2\ndiv w 2\nadd y w\nmod y 2\n\
+             div w 2\nadd x w\nmod x 2\ndiv w 2\nmod w 2",
+        )
+        .unwrap();
+        let regs = Alu::new(&program).run([13]).unwrap();
+        assert_eq!((regs.w, regs.x, regs.y, regs.z), (1, 1, 0, 1));
+
+        let program = parse_program("inp z\ninp x\nmul z 3\neql z x");
+        let program = program.unwrap();
+        assert_eq!(Alu::new(&program).run([2, 6]).unwrap().z, 1);
+        assert_eq!(Alu::new(&program).run([2, 7]).unwrap().z, 0);
+        let err = Alu::new(&program).run([2]).unwrap_err();
+        assert_eq!((err.pc, err.crash), (1, Crash::EndOfInput));
+    }
+
+    #[test]
+    fn crashes() {
+        for (text, crash) in [
+            ("inp x\ndiv x 0", Crash::DivisionByZero),
+            ("inp x\nmod x 0", Crash::InvalidModulo),
+            ("inp x\nmod x -3", Crash::InvalidModulo),
+            ("inp x\nmul x -1\nmod x 3", Crash::InvalidModulo),
+            ("inp x\nadd x 9223372036854775803", Crash::Overflow),
+            ("inp x\nmul x 2000000000000000000", Crash::Overflow),
+            (
+                "inp x\nadd y -9223372036854775808\ndiv y -1",
+                Crash::Overflow,
+            ),
+        ] {
+            let program = parse_program(text).unwrap();
+            let err = Alu::new(&program).run([5]).unwrap_err();
+            assert_eq!(err.crash, crash, "{}", text);
+            assert_eq!(err.pc, program.len() - 1, "{}", text);
+        }
+        let program = parse_program("inp x\ndiv x 0").unwrap();
+        let err = Error::from(Alu::new(&program).run([5]).unwrap_err());
+        assert_eq!(
+            err.to_string(),
+            "day 24, line 2: division by zero: \"div x 0\""
+        );
+    }
+}
@@ -0,0 +1,304 @@
+use std::fmt;
+
+use super::alu::{Crash, Instruction, Operand, Register, Regs};
+use super::optimize;
+
+/// An operation of a compiled program: an instruction of the ALU, or
+/// `set`, which the optimizer writes in place of `mul a 0` and `add a b`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    Inp(Register),
+    Set(Register, Operand),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    Div(Register, Operand),
+    Mod(Register, Operand),
+    Eql(Register, Operand),
+}
+
+impl Op {
+    /// The register the operation writes.
+    pub fn target(&self) -> Register {
+        match *self {
+            Op::Inp(a)
+            | Op::Set(a, _)
+            | Op::Add(a, _)
+            | Op::Mul(a, _)
+            | Op::Div(a, _)
+            | Op::Mod(a, _)
+            | Op::Eql(a, _) => a,
+        }
+    }
+
+    /// The second operand, unless the operation is `inp`.
+    pub fn operand(&self) -> Option<Operand> {
+        match *self {
+            Op::Inp(_) => None,
+            Op::Set(_, b)
+            | Op::Add(_, b)
+            | Op::Mul(_, b)
+            | Op::Div(_, b)
+            | Op::Mod(_, b)
+            | Op::Eql(_, b) => Some(b),
+        }
+    }
+
+    /// Whether the operation reads its target before writing it.
+    pub fn reads_target(&self) -> bool {
+        !matches!(self, Op::Inp(_) | Op::Set(..))
+    }
+
+    /// Whether the ALU may crash on the operation, overflows included.
+    pub fn may_crash(&self) -> bool {
+        match self {
+            Op::Inp(_) | Op::Set(..) | Op::Eql(..) => false,
+            Op::Add(_, Operand::Number(b)) => *b != 0,
+            Op::Mul(_, Operand::Number(b)) => !matches!(b, 0 | 1),
+            Op::Div(_, Operand::Number(b)) => matches!(b, 0 | -1),
+            _ => true,
+        }
+    }
+
+    /// Computes the value of an operation other than `inp`, which has no
+    /// digit to read here.
+    pub(crate) fn apply(&self, a: i64, b: i64) -> Result<i64, Crash> {
+        debug_assert!(!matches!(self, Op::Inp(_)), "inp has no value");
+        match self.kind() {
+            Some(kind) => kind.apply(a, b),
+            None => Err(Crash::EndOfInput),
+        }
+    }
+
+    fn kind(&self) -> Option<Kind> {
+        match self {
+            Op::Inp(_) => None,
+            Op::Set(..) => Some(Kind::Set),
+            Op::Add(..) => Some(Kind::Add),
+            Op::Mul(..) => Some(Kind::Mul),
+            Op::Div(..) => Some(Kind::Div),
+            Op::Mod(..) => Some(Kind::Mod),
+            Op::Eql(..) => Some(Kind::Eql),
+        }
+    }
+
+    /// The same operation on other operands.
+    pub fn with(&self, a: Register, b: Operand) -> Op {
+        match self {
+            Op::Inp(_) => Op::Inp(a),
+            Op::Set(..) => Op::Set(a, b),
+            Op::Add(..) => Op::Add(a, b),
+            Op::Mul(..) => Op::Mul(a, b),
+            Op::Div(..) => Op::Div(a, b),
+            Op::Mod(..) => Op::Mod(a, b),
+            Op::Eql(..) => Op::Eql(a, b),
+        }
+    }
+}
+
+impl From<Instruction> for Op {
+    fn from(inst: Instruction) -> Op {
+        match inst {
+            Instruction::Inp(a) => Op::Inp(a),
+            Instruction::Add(a, b) => Op::Add(a, b),
+            Instruction::Mul(a, b) => Op::Mul(a, b),
+            Instruction::Div(a, b) => Op::Div(a, b),
+            Instruction::Mod(a, b) => Op::Mod(a, b),
+            Instruction::Eql(a, b) => Op::Eql(a, b),
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Op::Inp(_) => "inp",
+            Op::Set(..) => "set",
+            Op::Add(..) => "add",
+            Op::Mul(..) => "mul",
+            Op::Div(..) => "div",
+            Op::Mod(..) => "mod",
+            Op::Eql(..) => "eql",
+        };
+        write!(f, "{} {}", name, self.target().name())?;
+        match self.operand() {
+            Some(Operand::Register(b)) => write!(f, " {}", b.name()),
+            Some(Operand::Number(b)) => write!(f, " {}", b),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A program compiled to operations on an array of registers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Program {
+    ops: Vec<Op>,
+    code: Vec<Code>,
+}
+
+/// An operation with its registers as indices, and its second operand
+/// either a register or an immediate number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Code {
+    Inp,
+    Reg(Kind, u8, u8),
+    Imm(Kind, u8, i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Set,
+    Add,
+    Mul,
+    Div,
+    Mod,
+    Eql,
+}
+
+impl Kind {
+    #[inline]
+    fn apply(self, a: i64, b: i64) -> Result<i64, Crash> {
+        let v = match self {
+            Kind::Set => b,
+            Kind::Add => a.checked_add(b).ok_or(Crash::Overflow)?,
+            Kind::Mul => a.checked_mul(b).ok_or(Crash::Overflow)?,
+            Kind::Div if b == 0 => return Err(Crash::DivisionByZero),
+            Kind::Div => a.checked_div(b).ok_or(Crash::Overflow)?,
+            Kind::Mod if a < 0 || b <= 0 => return Err(Crash::InvalidModulo),
+            Kind::Mod => a % b,
+            Kind::Eql => (a == b) as i64,
+        };
+        Ok(v)
+    }
+}
+
+impl Program {
+    /// Compiles the instructions as they are.
+    pub fn compile(program: &[Instruction]) -> Program {
+        Program::from_ops(
+            program.iter().map(|&inst| Op::from(inst)).collect(),
+        )
+    }
+
+    /// Compiles the instructions after optimizing them, so that only the
+    /// final values of the `live` registers are kept.
+    pub fn optimize(program: &[Instruction], live: &[Register]) -> Program {
+        let ops = program.iter().map(|&inst| Op::from(inst)).collect();
+        Program::from_ops(optimize::optimize(ops, live))
+    }
+
+    pub fn from_ops(ops: Vec<Op>) -> Program {
+        let code = ops
+            .iter()
+            .map(|op| match (op.kind(), op.operand()) {
+                (Some(kind), Some(Operand::Register(b))) => {
+                    Code::Reg(kind, index(op.target()), index(b))
+                }
+                (Some(kind), Some(Operand::Number(b))) => {
+                    Code::Imm(kind, index(op.target()), b)
+                }
+                _ => Code::Inp,
+            })
+            .collect();
+        Program { ops, code }
+    }
+
+    pub fn ops(&self) -> &[Op] {
+        &self.ops
+    }
+
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    /// Runs the program from zeroed registers, reading `inp` from `input`,
+    /// and returns the registers at the end.
+    pub fn run(
+        &self,
+        input: impl IntoIterator<Item = i64>,
+    ) -> Result<Regs, Crash> {
+        let mut input = input.into_iter();
+        let mut regs = Regs::default();
+        let mut pc = 0;
+        while pc < self.code.len() {
+            if let Code::Inp = self.code[pc] {
+                let digit = input.next().ok_or(Crash::EndOfInput)?;
+                pc = self.resume(pc, &mut regs, digit)?;
+            } else {
+                pc = self.run_until_input(pc, &mut regs)?;
+            }
+        }
+        Ok(regs)
+    }
+
+    /// Runs the `inp` at `pc` with the digit, then every operation up to
+    /// the next `inp` or the end, and returns where it stopped. `pc` must
+    /// be where [`Program::run_until_input`] stopped before the end.
+    pub(crate) fn resume(
+        &self,
+        pc: usize,
+        regs: &mut Regs,
+        digit: i64,
+    ) -> Result<usize, Crash> {
+        debug_assert!(matches!(self.code[pc], Code::Inp), "no inp at {}", pc);
+        if let Op::Inp(a) = self.ops[pc] {
+            regs.set(a, digit);
+        }
+        self.run_until_input(pc + 1, regs)
+    }
+
+    /// Runs every operation from `pc` up to the next `inp` or the end, and
+    /// returns where it stopped.
+    pub fn run_until_input(
+        &self,
+        mut pc: usize,
+        regs: &mut Regs,
+    ) -> Result<usize, Crash> {
+        let mut r = to_array(regs);
+        while let Some(&code) = self.code.get(pc) {
+            match code {
+                Code::Inp => break,
+                Code::Reg(kind, a, b) => {
+                    let (a, b) = (a as usize, b as usize);
+                    r[a] = kind.apply(r[a], r[b])?;
+                }
+                Code::Imm(kind, a, b) => {
+                    let a = a as usize;
+                    r[a] = kind.apply(r[a], b)?;
+                }
+            }
+            pc += 1;
+        }
+        *regs = from_array(r);
+        Ok(pc)
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for op in &self.ops {
+            writeln!(f, "{}", op)?;
+        }
+        Ok(())
+    }
+}
+
+fn index(r: Register) -> u8 {
+    match r {
+        Register::W => 0,
+        Register::X => 1,
+        Register::Y => 2,
+        Register::Z => 3,
+    }
+}
+
+fn to_array(regs: &Regs) -> [i64; 4] {
+    [regs.w, regs.x, regs.y, regs.z]
+}
+
+fn from_array([w, x, y, z]: [i64; 4]) -> Regs {
+    Regs { w, x, y, z }
+}
@@ -0,0 +1,273 @@
+use std::collections::{HashMap, HashSet};
+
+use super::alu::{Alu, Instruction, Regs};
+use super::compile::Program;
+use super::DAY;
+use crate::{Error, Result};
+
+/// Whether the digits, one per `inp`, form a valid model number.
+pub fn validate(program: &[Instruction], digits: &[u8]) -> Result<bool> {
+    let inputs = program
+        .iter()
+        .filter(|inst| matches!(inst, Instruction::Inp(_)))
+        .count();
+    if digits.len() != inputs {
+        let msg = format!("{} digits for {} inputs", digits.len(), inputs);
+        return Err(Error::new(DAY, msg));
+    }
+    if let Some(d) = digits.iter().find(|d| !(1..=9).contains(*d)) {
+        let msg = format!("invalid digit {} in a model number", d);
+        return Err(Error::new(DAY, msg));
+    }
+    let digits = digits.iter().map(|&d| d as i64);
+    let regs = Alu::new(program).run(digits)?;
+    Ok(regs.z == 0)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Ascending,
+    Descending,
+}
+
+/// The valid model numbers of a program, found lazily in order.
+pub struct ModelNumbers<'a> {
+    program: &'a Program,
+    digits: [u8; 9],
+    z_limit: Option<i64>,
+    /// The states from which no valid model number goes.
+    dead: HashSet<(usize, Regs)>,
+    /// A frame per digit being tried, and the digits picked before it.
+    stack: Vec<Frame>,
+    prefix: Vec<u8>,
+}
+
+struct Frame {
+    /// The `inp` that reads the digit, and the registers before it.
+    pc: usize,
+    regs: Regs,
+    /// The index of the next digit to try.
+    next: usize,
+    found: bool,
+}
+
+impl<'a> ModelNumbers<'a> {
+    pub fn new(program: &'a Program, order: Order) -> ModelNumbers<'a> {
+        let mut digits = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+        if order == Order::Descending {
+            digits.reverse();
+        }
+        let mut regs = Regs::default();
+        let stack = match program.run_until_input(0, &mut regs) {
+            Ok(pc) if pc < program.len() => vec![Frame {
+                pc,
+                regs,
+                next: 0,
+                found: false,
+            }],
+            _ => Vec::new(),
+        };
+        ModelNumbers {
+            program,
+            digits,
+            z_limit: None,
+            dead: HashSet::new(),
+            stack,
+            prefix: Vec::new(),
+        }
+    }
+
+    /// Skips the states where `z` is above the limit, assuming it cannot
+    /// get back to zero from there. That holds for MONAD, whose `z` grows
+    /// and shrinks by factors of 26, but valid model numbers of other
+    /// programs may be missed.
+    pub fn with_z_limit(mut self, limit: i64) -> ModelNumbers<'a> {
+        self.z_limit = Some(limit);
+        self
+    }
+}
+
+impl Iterator for ModelNumbers<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        loop {
+            let frame = self.stack.last_mut()?;
+            if frame.next == self.digits.len() {
+                let frame = self.stack.pop().unwrap();
+                if !frame.found {
+                    self.dead.insert((frame.pc, frame.regs));
+                }
+                if let Some(parent) = self.stack.last_mut() {
+                    parent.found |= frame.found;
+                    self.prefix.pop();
+                }
+                continue;
+            }
+            let d = self.digits[frame.next];
+            frame.next += 1;
+            let mut regs = frame.regs;
+            // no model number goes where the ALU crashes
+            let pc = match self.program.resume(frame.pc, &mut regs, d as i64)
+            {
+                Ok(pc) => pc,
+                Err(_) => continue,
+            };
+            let too_large = self.z_limit.is_some_and(|limit| regs.z > limit);
+            if too_large || self.dead.contains(&(pc, regs)) {
+                continue;
+            }
+            if pc >= self.program.len() {
+                if regs.z != 0 {
+                    continue;
+                }
+                frame.found = true;
+                let digits = self.prefix.iter().chain([&d]);
+                return Some(digits.fold(0, |n, &d| n * 10 + d as u64));
+            }
+            self.prefix.push(d);
+            self.stack.push(Frame {
+                pc,
+                regs,
+                next: 0,
+                found: false,
+            });
+        }
+    }
+}
+
+/// Counts the valid model numbers of a program, skipping the states where
+/// `z` is above `z_limit` as [`ModelNumbers::with_z_limit`] does.
+pub fn count(program: &Program, z_limit: Option<i64>) -> u64 {
+    fn count_from(
+        program: &Program,
+        z_limit: Option<i64>,
+        memo: &mut HashMap<(usize, Regs), u64>,
+        pc: usize,
+        regs: Regs,
+    ) -> u64 {
+        if pc >= program.len() {
+            return (regs.z == 0) as u64;
+        }
+        if z_limit.is_some_and(|limit| regs.z > limit) {
+            return 0;
+        }
+        if let Some(&n) = memo.get(&(pc, regs)) {
+            return n;
+        }
+        let mut n = 0;
+        for d in 1..=9 {
+            let mut new_regs = regs;
+            if let Ok(next) = program.resume(pc, &mut new_regs, d) {
+                n += count_from(program, z_limit, memo, next, new_regs);
+            }
+        }
+        memo.insert((pc, regs), n);
+        n
+    }
+
+    let mut regs = Regs::default();
+    match program.run_until_input(0, &mut regs) {
+        Ok(pc) if pc < program.len() => {
+            count_from(program, z_limit, &mut HashMap::new(), pc, regs)
+        }
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::monad::Monad;
+    use super::super::{parse_program, Register};
+    use super::*;
+    use crate::read_example;
+
+    /// Far above what MONAD needs, as its `z` holds at most 7 base 26
+    /// digits half way through, and must shrink to 0 after.
+    const Z_LIMIT: i64 = 1_000_000;
+
+    fn digits(n: u64) -> Vec<u8> {
+        n.to_string().bytes().map(|b| b - b'0').collect()
+    }
+
+    #[test]
+    fn validator() {
+        let program = parse_program(&read_example(24)).unwrap();
+        let monad = Monad::analyze(&program).unwrap();
+        let largest = monad.largest().unwrap();
+        assert!(validate(&program, &digits(largest)).unwrap());
+        assert!(!validate(&program, &digits(largest - 1)).unwrap());
+        assert!(validate(&program, &digits(largest / 10)).is_err());
+        assert!(validate(&program, &digits(10_000_000_000_000)).is_err());
+
+        let program = parse_program("inp w\nmod w -1").unwrap();
+        let err = validate(&program, &[5]).unwrap_err();
+        assert_eq!(err.line, Some(2));
+    }
+
+    #[test]
+    fn enumerator() {
+        let instructions = parse_program(&read_example(24)).unwrap();
+        let monad = Monad::analyze(&instructions).unwrap();
+        let program = Program::optimize(&instructions, &[Register::Z]);
+
+        let expected: u64 = monad
+            .constraints
+            .iter()
+            .map(|c| 9 - c.delta.unsigned_abs())
+            .product();
+        assert_eq!(count(&program, Some(Z_LIMIT)), expected);
+
+        let ascending: Vec<u64> =
+            ModelNumbers::new(&program, Order::Ascending)
+                .with_z_limit(Z_LIMIT)
+                .take(50)
+                .collect();
+        assert_eq!(ascending[0], monad.smallest().unwrap());
+        assert!(ascending.windows(2).all(|w| w[0] < w[1]));
+        let mut descending = ModelNumbers::new(&program, Order::Descending)
+            .with_z_limit(Z_LIMIT);
+        assert_eq!(descending.next(), monad.largest());
+        for n in descending.take(50).chain(ascending) {
+            assert!(validate(&instructions, &digits(n)).unwrap(), "{}", n);
+        }
+    }
+
+    #[test]
+    fn every_number() {
+        // valid when the second digit is the first plus one, or is 9
+        let program = parse_program(
+            "inp z\ninp x\nadd z 1\neql z x\ninp y\neql x 9\nadd z x\n\
+             eql z 0",
+        )
+        .unwrap();
+        let program = Program::compile(&program);
+        let all: Vec<u64> =
+            ModelNumbers::new(&program, Order::Ascending).collect();
+        assert_eq!(all.len() as u64, count(&program, None));
+        assert_eq!(all.len(), 16 * 9);
+        assert_eq!(all[..3], [121, 122, 123]);
+        let mut descending: Vec<u64> =
+            ModelNumbers::new(&program, Order::Descending).collect();
+        descending.reverse();
+        assert_eq!(descending, all);
+    }
+
+    #[test]
+    fn large_z() {
+        // valid when the first digit is 9, through a z of 9 million
+        let program = parse_program(&format!(
+            "inp z\nmul z 1000000\n{}div z 1000000\neql z 9\neql z 0",
+            "inp w\n".repeat(13)
+        ))
+        .unwrap();
+        let program = Program::compile(&program);
+        assert_eq!(count(&program, None), 9u64.pow(13));
+        assert_eq!(count(&program, Some(1_000_000)), 0);
+        let mut numbers = ModelNumbers::new(&program, Order::Ascending);
+        assert_eq!(numbers.next(), Some(91_111_111_111_111));
+        let mut limited = ModelNumbers::new(&program, Order::Ascending)
+            .with_z_limit(1_000_000);
+        assert_eq!(limited.next(), None);
+    }
+}
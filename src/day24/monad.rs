@@ -0,0 +1,253 @@
+use super::alu::{Instruction, Operand, Register};
+
+/// The number of digits of a model number, and of blocks of MONAD.
+pub const DIGITS: usize = 14;
+
+/// Every block of MONAD reads a digit `w` then runs these instructions,
+/// but for its parameters at `DIV`, `CHECK` and `OFFSET`:
+///
+/// ```text
+/// x = z % 26 + check != w
+/// z = z / div * (25 * x + 1) + (w + offset) * x
+/// ```
+///
+/// That is, `z` is a stack of base 26 digits: a block with a `div` of 1
+/// and a `check` above 9 always pushes `w + offset`, and a block with a
+/// `div` of 26 pops the top, and pushes nothing only if it plus `check` is
+/// `w`. As long as every `w + offset` is a non-zero digit, `z` ends at 0
+/// only if every pop pushes nothing.
+const BLOCK: &str = "\
+inp w
+mul x 0
+add x z
+mod x 26
+div z 0
+add x 0
+eql x w
+eql x 0
+mul y 0
+add y 25
+mul y x
+add y 1
+mul z y
+mul y 0
+add y w
+add y 0
+mul y x
+add z y";
+
+const DIV: usize = 4;
+const CHECK: usize = 5;
+const OFFSET: usize = 15;
+
+/// The parameters of a block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Block {
+    pub div: i64,
+    pub check: i64,
+    pub offset: i64,
+}
+
+/// Digit `right` must be digit `left` plus `delta`, counting digits from
+/// the left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Constraint {
+    pub left: usize,
+    pub right: usize,
+    pub delta: i64,
+}
+
+/// What makes a model number valid, derived from the program of MONAD.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monad {
+    pub blocks: Vec<Block>,
+    pub constraints: Vec<Constraint>,
+}
+
+impl Monad {
+    /// Analyzes a program, unless it does not follow the pattern of MONAD.
+    pub fn analyze(program: &[Instruction]) -> Option<Monad> {
+        let blocks = blocks(program)?;
+        let constraints = constraints(&blocks)?;
+        Some(Monad {
+            blocks,
+            constraints,
+        })
+    }
+
+    /// The largest valid model number, if any.
+    pub fn largest(&self) -> Option<u64> {
+        self.model_number(9, |delta| (9 - delta).min(9))
+    }
+
+    /// The smallest valid model number, if any.
+    pub fn smallest(&self) -> Option<u64> {
+        self.model_number(1, |delta| (1 - delta).max(1))
+    }
+
+    /// Picks the left digit of every constraint from its delta, and any
+    /// other digit as `free`.
+    fn model_number(
+        &self,
+        free: i64,
+        left: impl Fn(i64) -> i64,
+    ) -> Option<u64> {
+        let mut digits = [free; DIGITS];
+        for c in &self.constraints {
+            digits[c.left] = left(c.delta);
+            digits[c.right] = digits[c.left] + c.delta;
+        }
+        if digits.iter().any(|d| !(1..=9).contains(d)) {
+            return None;
+        }
+        Some(digits.iter().fold(0, |n, &d| n * 10 + d as u64))
+    }
+}
+
+/// Splits the program into its blocks, and returns their parameters.
+fn blocks(program: &[Instruction]) -> Option<Vec<Block>> {
+    let template = super::parse_program(BLOCK).unwrap();
+    if program.len() != template.len() * DIGITS {
+        return None;
+    }
+    let mut blocks = Vec::with_capacity(DIGITS);
+    for block in program.chunks(template.len()) {
+        for (i, (inst, expected)) in block.iter().zip(&template).enumerate() {
+            if ![DIV, CHECK, OFFSET].contains(&i) && inst != expected {
+                return None;
+            }
+        }
+        let (div, check, offset) =
+            match (block[DIV], block[CHECK], block[OFFSET]) {
+                (
+                    Instruction::Div(Register::Z, Operand::Number(div)),
+                    Instruction::Add(Register::X, Operand::Number(check)),
+                    Instruction::Add(Register::Y, Operand::Number(offset)),
+                ) => (div, check, offset),
+                _ => return None,
+            };
+        blocks.push(Block { div, check, offset });
+    }
+    Some(blocks)
+}
+
+/// Pairs every block that pushes with the block that pops it, which
+/// yields a digit constraint per pair.
+fn constraints(blocks: &[Block]) -> Option<Vec<Constraint>> {
+    let mut stack = Vec::new();
+    let mut constraints = Vec::new();
+    for (i, block) in blocks.iter().enumerate() {
+        // `w + offset` must be a non-zero base 26 digit, for any `w`
+        if !(0..=16).contains(&block.offset) {
+            return None;
+        }
+        match block.div {
+            1 if block.check > 9 && block.offset > 0 => {
+                stack.push((i, block.offset))
+            }
+            26 => {
+                let (left, offset) = stack.pop()?;
+                let delta = offset + block.check;
+                constraints.push(Constraint {
+                    left,
+                    right: i,
+                    delta,
+                });
+            }
+            _ => return None,
+        }
+    }
+    match stack.is_empty() {
+        true => Some(constraints),
+        false => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{parse_program, Alu};
+    use super::*;
+    use crate::read_example;
+
+    fn digits(n: u64) -> Vec<i64> {
+        n.to_string().bytes().map(|b| (b - b'0') as i64).collect()
+    }
+
+    #[test]
+    fn analysis() {
+        let program = parse_program(&read_example(24)).unwrap();
+        let monad = Monad::analyze(&program).unwrap();
+        assert_eq!(
+            monad.blocks[4],
+            Block {
+                div: 26,
+                check: -11,
+                offset: 12
+            }
+        );
+        assert_eq!(monad.constraints.len(), 7);
+        assert_eq!(
+            monad.constraints[0],
+            Constraint {
+                left: 3,
+                right: 4,
+                delta: -1
+            }
+        );
+        for n in [monad.largest().unwrap(), monad.smallest().unwrap()] {
+            let regs = Alu::new(&program).run(digits(n)).unwrap();
+            assert_eq!(regs.z, 0, "{}", n);
+        }
+    }
+
+    #[test]
+    fn other_programs() {
+        let program = parse_program(&read_example(24)).unwrap();
+        assert!(Monad::analyze(&program[..18 * 13]).is_none());
+        let mut changed = program.clone();
+        changed[18 * 3 + 9] =
+            Instruction::Add(Register::Y, Operand::Number(24));
+        assert!(Monad::analyze(&changed).is_none());
+
+        // A pair of digits that can never differ by 9.
+        let mut blocks = Monad::analyze(&program).unwrap().blocks;
+        blocks[4].check = -30;
+        let constraints = constraints(&blocks).unwrap();
+        let monad = Monad {
+            blocks,
+            constraints,
+        };
+        assert_eq!(monad.largest(), None);
+        assert_eq!(monad.smallest(), None);
+    }
+
+    #[test]
+    fn broken_pairs() {
+        // a failed pop of the first pair pushes `9 - 9`, which leaves z at
+        // 0 whatever the first digit
+        let mut blocks = vec![(1, 10, 5), (26, -10, -9)];
+        blocks.extend([(1, 10, 5), (26, -5, 5)].repeat(6));
+        let template = parse_program(BLOCK).unwrap();
+        let mut program = Vec::new();
+        for (div, check, offset) in blocks {
+            let mut block = template.clone();
+            block[DIV] = Instruction::Div(Register::Z, Operand::Number(div));
+            block[CHECK] =
+                Instruction::Add(Register::X, Operand::Number(check));
+            block[OFFSET] =
+                Instruction::Add(Register::Y, Operand::Number(offset));
+            program.extend(block);
+        }
+        assert!(Monad::analyze(&program).is_none());
+
+        let input = super::super::Input { program };
+        let largest = super::super::part_one(&input).unwrap();
+        let smallest = super::super::part_two(&input).unwrap();
+        assert_eq!(largest, 99_999_999_999_999);
+        assert_eq!(smallest, 19_111_111_111_111);
+        for n in [largest, smallest] {
+            let regs = Alu::new(&input.program).run(digits(n)).unwrap();
+            assert_eq!(regs.z, 0, "{}", n);
+        }
+    }
+}
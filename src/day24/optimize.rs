@@ -0,0 +1,221 @@
+use super::alu::{Operand, Register};
+use super::compile::Op;
+
+const REGISTERS: [Register; 4] =
+    [Register::W, Register::X, Register::Y, Register::Z];
+
+/// Runs the passes until none changes the program, keeping the final
+/// values of the `live` registers and every crash of the ALU.
+pub fn optimize(mut ops: Vec<Op>, live: &[Register]) -> Vec<Op> {
+    loop {
+        let folded = fold_constants(&ops);
+        let propagated = propagate_copies(&folded);
+        let optimized = eliminate_dead_stores(&propagated, live);
+        if optimized == ops {
+            return ops;
+        }
+        ops = optimized;
+    }
+}
+
+/// Tracks the registers whose value is known, all zero at the start, to
+/// replace operands with their values, compute operations on known values,
+/// and drop the operations that change nothing.
+fn fold_constants(ops: &[Op]) -> Vec<Op> {
+    let mut known: [Option<i64>; 4] = [Some(0); 4];
+    let mut folded = Vec::with_capacity(ops.len());
+    for op in ops {
+        let a = op.target();
+        let b = match op.operand() {
+            Some(Operand::Register(b)) => match known[index(b)] {
+                Some(n) => Some(Operand::Number(n)),
+                None => Some(Operand::Register(b)),
+            },
+            b => b,
+        };
+        let value = |b| match b {
+            Some(Operand::Number(n)) => Some(n),
+            _ => None,
+        };
+        let op = match b {
+            Some(b) => op.with(a, b),
+            None => *op,
+        };
+        let (op, value) = match (op, known[index(a)], value(b)) {
+            (Op::Inp(_), ..) => (Some(op), None),
+            (_, Some(x), Some(y)) => match op.apply(x, y) {
+                Ok(v) if known[index(a)] == Some(v) => (None, Some(v)),
+                Ok(v) => (Some(Op::Set(a, Operand::Number(v))), Some(v)),
+                // keep the crash
+                Err(_) => (Some(op), None),
+            },
+            (Op::Set(_, b), ..) if b == Operand::Register(a) => {
+                (None, known[index(a)])
+            }
+            (Op::Set(..), ..) => (Some(op), value(b)),
+            (Op::Add(_, b), Some(0), _) => {
+                (Some(Op::Set(a, b)), value(Some(b)))
+            }
+            (Op::Mul(_, b), Some(1), _) => {
+                (Some(Op::Set(a, b)), value(Some(b)))
+            }
+            (Op::Mul(..), Some(0), _) | (Op::Mul(..), _, Some(0)) => {
+                (Some(Op::Set(a, Operand::Number(0))), Some(0))
+            }
+            (Op::Add(..), _, Some(0))
+            | (Op::Mul(..), _, Some(1))
+            | (Op::Div(..), _, Some(1)) => (None, None),
+            _ => (Some(op), None),
+        };
+        // dropping an operation leaves the register as it was
+        known[index(a)] = match op {
+            Some(_) => value,
+            None => known[index(a)],
+        };
+        folded.extend(op);
+    }
+    folded
+}
+
+/// Replaces the operands that are copies of another register with that
+/// register, so that the copy may become a dead store.
+fn propagate_copies(ops: &[Op]) -> Vec<Op> {
+    let mut copy_of: [Option<Register>; 4] = [None; 4];
+    let mut propagated = Vec::with_capacity(ops.len());
+    for op in ops {
+        let a = op.target();
+        let b = match op.operand() {
+            Some(Operand::Register(b)) => {
+                Some(Operand::Register(copy_of[index(b)].unwrap_or(b)))
+            }
+            b => b,
+        };
+        let op = match b {
+            Some(b) => op.with(a, b),
+            None => *op,
+        };
+        if op == Op::Set(a, Operand::Register(a)) {
+            continue;
+        }
+        for r in REGISTERS {
+            if copy_of[index(r)] == Some(a) {
+                copy_of[index(r)] = None;
+            }
+        }
+        copy_of[index(a)] = match op {
+            Op::Set(_, Operand::Register(b)) => Some(b),
+            _ => None,
+        };
+        propagated.push(op);
+    }
+    propagated
+}
+
+/// Drops the operations whose result is overwritten before it is read,
+/// or never read, unless the ALU may crash on them.
+fn eliminate_dead_stores(ops: &[Op], live: &[Register]) -> Vec<Op> {
+    let mut is_live = [false; 4];
+    for &r in live {
+        is_live[index(r)] = true;
+    }
+    let mut kept = Vec::with_capacity(ops.len());
+    for op in ops.iter().rev() {
+        let a = index(op.target());
+        // an `inp` always reads a digit
+        let needed = is_live[a] || op.may_crash() || matches!(op, Op::Inp(_));
+        if !needed {
+            continue;
+        }
+        is_live[a] = op.reads_target();
+        if let Some(Operand::Register(b)) = op.operand() {
+            is_live[index(b)] = true;
+        }
+        kept.push(*op);
+    }
+    kept.reverse();
+    kept
+}
+
+fn index(r: Register) -> usize {
+    match r {
+        Register::W => 0,
+        Register::X => 1,
+        Register::Y => 2,
+        Register::Z => 3,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::alu::{Alu, Crash};
+    use super::super::compile::Program;
+    use super::super::parse_program;
+    use super::*;
+    use crate::read_example;
+
+    fn optimized(program: &str, live: &[Register]) -> String {
+        let program = parse_program(program).unwrap();
+        Program::optimize(&program, live).to_string()
+    }
+
+    #[test]
+    fn passes() {
+        let all = &REGISTERS;
+        assert_eq!(
+            optimized("inp w\nmul x 0\nadd x w", all),
+            "inp w\nset x w\n"
+        );
+        assert_eq!(
+            optimized("add x 3\nmul x 2\ninp y\nadd y x\ndiv y 1", all),
+            "set x 6\ninp y\nadd y 6\n"
+        );
+        assert_eq!(
+            optimized("inp w\nadd x w\nadd z x\nmul x 0", &[Register::Z]),
+            "inp w\nset z w\n"
+        );
+        // crashes stay, even when their result is dead
+        assert_eq!(
+            optimized("inp w\nmod w 2\ndiv x 0\ninp w", &[Register::Z]),
+            "inp w\nmod w 2\ndiv x 0\ninp w\n"
+        );
+        // and so do overflows, which are left unfolded
+        assert_eq!(
+            optimized(
+                "inp w\nadd x -9223372036854775808\ndiv x -1\nadd y 1",
+                &[Register::Z]
+            ),
+            "inp w\nset x -9223372036854775808\ndiv x -1\n"
+        );
+        let program =
+            parse_program("inp w\nadd x -9223372036854775808\ndiv x -1")
+                .unwrap();
+        let program = Program::optimize(&program, &[Register::Z]);
+        assert_eq!(program.run([1]), Err(Crash::Overflow));
+        assert_eq!(
+            optimized("inp w\nadd w 1\nmul w 3\nadd y 1", &[Register::Z]),
+            "inp w\nadd w 1\nmul w 3\n"
+        );
+    }
+
+    #[test]
+    fn same_results() {
+        let program = parse_program(&read_example(24)).unwrap();
+        let compiled = Program::compile(&program);
+        let optimized = Program::optimize(&program, &[Register::Z]);
+        assert!(optimized.len() < program.len() * 4 / 5);
+        let mut seed: u64 = 0x2021_1224;
+        for _ in 0..200 {
+            let digits: Vec<i64> = (0..14)
+                .map(|_| {
+                    seed ^= seed << 13;
+                    seed ^= seed >> 7;
+                    seed ^= seed << 17;
+                    1 + (seed % 9) as i64
+                })
+                .collect();
+            let regs = Alu::new(&program).run(digits.clone()).unwrap();
+            assert_eq!(compiled.run(digits.clone()).unwrap(), regs);
+            assert_eq!(optimized.run(digits).unwrap().z, regs.z);
+        }
+    }
+}
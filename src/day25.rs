@@ -1,27 +1,50 @@
 use std::collections::HashMap;
 
-type Sea = HashMap<(usize, usize), char>;
+use crate::{Error, Result};
 
-fn parse_input(input: &str) -> (usize, usize, Sea) {
+const DAY: u8 = 25;
+
+solution!(DAY, "Sea Cucumber");
+
+pub type Sea = HashMap<(usize, usize), char>;
+
+pub struct Input {
+    pub width: usize,
+    pub height: usize,
+    pub sea: Sea,
+}
+
+pub fn parse(input: &str) -> Result<Input> {
     let mut height = 0;
     let mut width = 0;
 
     let mut sea: Sea = Sea::new();
     for (y, line) in input.lines().enumerate() {
+        if line.is_empty() || (y > 0 && line.len() != width) {
+            return Err(Error::new(DAY, "invalid row").at(y + 1, line));
+        }
         for (x, v) in line.chars().enumerate() {
-            if v == '>' || v == 'v' {
-                sea.insert((x, y), v);
+            match v {
+                '>' | 'v' => {
+                    sea.insert((x, y), v);
+                }
+                '.' => {}
+                _ => {
+                    let err = Error::new(DAY, "invalid sea cucumber");
+                    return Err(err.at(y + 1, line));
+                }
             }
         }
         width = line.len();
         height += 1;
     }
 
-    (width, height, sea)
+    Ok(Input { width, height, sea })
 }
 
-pub fn part_one(input: &str) -> i32 {
-    let (width, height, mut sea) = parse_input(input);
+pub fn part_one(input: &Input) -> Result<i32> {
+    let (width, height) = (input.width, input.height);
+    let mut sea = input.sea.clone();
     let mut i = 0;
     loop {
         i += 1;
@@ -52,13 +75,17 @@ pub fn part_one(input: &str) -> i32 {
         sea = new_sea;
 
         if !is_moved {
-            break i;
+            break Ok(i);
         }
     }
 }
 
-pub fn part_two(_: &str) -> String {
-    String::from("Sleigh keys detected!")
+pub fn part_two(_: &Input) -> Result<String> {
+    Ok(String::from("Sleigh keys detected!"))
+}
+
+pub fn solve(input: &Input) -> Result<(i32, String)> {
+    Ok((part_one(input)?, part_two(input)?))
 }
 
 #[cfg(test)]
@@ -68,7 +95,7 @@ mod tests {
 
     #[test]
     fn example_one() {
-        let input = read_example(25);
-        assert_eq!(part_one(&input), 58);
+        let input = parse(&read_example(25)).unwrap();
+        assert_eq!(part_one(&input).unwrap(), 58);
     }
 }
@@ -1,4 +1,65 @@
+use std::any::Any;
+use std::env;
+use std::fmt;
 use std::fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+
+use answers::AnswerFiles;
+
+/// Implements [`Solution`] for a `Puzzle` struct in a day module, in terms
+/// of the module's `Input` type and `parse`, `part_one`, `part_two` and
+/// `solve` functions.
+macro_rules! solution {
+    ($day:expr, $title:expr) => {
+        pub struct Puzzle;
+
+        impl $crate::Solution for Puzzle {
+            fn day(&self) -> u8 {
+                $day
+            }
+
+            fn title(&self) -> &'static str {
+                $title
+            }
+
+            fn parse(&self, input: &str) -> $crate::Result<$crate::Parsed> {
+                Ok(Box::new(parse(input)?))
+            }
+
+            fn part_one(
+                &self,
+                input: &$crate::Parsed,
+            ) -> $crate::Result<$crate::Answer> {
+                let input = $crate::downcast::<Input>($day, input)?;
+                Ok(Box::new(part_one(input)?))
+            }
+
+            fn part_two(
+                &self,
+                input: &$crate::Parsed,
+            ) -> $crate::Result<$crate::Answer> {
+                let input = $crate::downcast::<Input>($day, input)?;
+                Ok(Box::new(part_two(input)?))
+            }
+
+            fn solve(
+                &self,
+                input: &$crate::Parsed,
+            ) -> $crate::Result<($crate::Answer, $crate::Answer)> {
+                let input = $crate::downcast::<Input>($day, input)?;
+                let (one, two) = solve(input)?;
+                Ok((Box::new(one), Box::new(two)))
+            }
+        }
+    };
+}
+
+pub mod answers;
+pub mod bench;
+pub mod pool;
+pub mod record;
+pub mod select;
 
 pub mod day01;
 pub mod day02;
@@ -26,14 +87,255 @@ pub mod day23;
 pub mod day24;
 pub mod day25;
 
-pub fn read_as_string(day: u8, filename: &str) -> String {
-    let filename = format!("inputs/{:02}-{}.txt", day, filename);
-    fs::read_to_string(filename).unwrap()
+/// An error raised while parsing or solving the input of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    /// The day of the puzzle that failed.
+    pub day: u8,
+    /// The 1-based line number of the offending input, if known.
+    pub line: Option<usize>,
+    /// The offending text, if any.
+    pub text: Option<String>,
+    pub message: String,
+}
+
+impl Error {
+    pub fn new(day: u8, message: impl Into<String>) -> Error {
+        Error {
+            day,
+            line: None,
+            text: None,
+            message: message.into(),
+        }
+    }
+
+    /// Attaches the 1-based line number and the offending text.
+    pub fn at(mut self, line: usize, text: &str) -> Error {
+        self.line = Some(line);
+        self.text = Some(text.to_string());
+        self
+    }
 }
 
-pub fn read_input(day: u8) -> String {
-    read_as_string(day, "input")
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "day {}", self.day)?;
+        if let Some(line) = self.line {
+            write!(f, ", line {}", line)?;
+        }
+        write!(f, ": {}", self.message)?;
+        if let Some(text) = &self.text {
+            write!(f, ": {:?}", text)?;
+        }
+        Ok(())
+    }
 }
+
+impl std::error::Error for Error {}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// The parsed input of a puzzle, as returned by [`Solution::parse`].
+pub type Parsed = Box<dyn Any + Send + Sync>;
+
+/// The answer to one part of a puzzle.
+pub type Answer = Box<dyn fmt::Display + Send>;
+
+/// A puzzle of the Advent of Code 2021.
+pub trait Solution: Sync {
+    fn day(&self) -> u8;
+
+    fn title(&self) -> &'static str;
+
+    fn parse(&self, input: &str) -> Result<Parsed>;
+
+    /// Solves the first part, given the result of [`Solution::parse`].
+    fn part_one(&self, input: &Parsed) -> Result<Answer>;
+
+    /// Solves the second part, given the result of [`Solution::parse`].
+    fn part_two(&self, input: &Parsed) -> Result<Answer>;
+
+    /// Solves both parts at once, sharing the work they have in common.
+    fn solve(&self, input: &Parsed) -> Result<(Answer, Answer)>;
+}
+
+static REGISTRY: [&dyn Solution; 25] = [
+    &day01::Puzzle,
+    &day02::Puzzle,
+    &day03::Puzzle,
+    &day04::Puzzle,
+    &day05::Puzzle,
+    &day06::Puzzle,
+    &day07::Puzzle,
+    &day08::Puzzle,
+    &day09::Puzzle,
+    &day10::Puzzle,
+    &day11::Puzzle,
+    &day12::Puzzle,
+    &day13::Puzzle,
+    &day14::Puzzle,
+    &day15::Puzzle,
+    &day16::Puzzle,
+    &day17::Puzzle,
+    &day18::Puzzle,
+    &day19::Puzzle,
+    &day20::Puzzle,
+    &day21::Puzzle,
+    &day22::Puzzle,
+    &day23::Puzzle,
+    &day24::Puzzle,
+    &day25::Puzzle,
+];
+
+/// Returns all the puzzles, ordered by day.
+pub fn registry() -> &'static [&'static dyn Solution] {
+    &REGISTRY
+}
+
+/// Returns the puzzle of the given day, if there is one.
+pub fn solution(day: u8) -> Option<&'static dyn Solution> {
+    REGISTRY.iter().copied().find(|s| s.day() == day)
+}
+
+fn downcast<T: Any>(day: u8, input: &Parsed) -> Result<&T> {
+    input
+        .downcast_ref()
+        .ok_or_else(|| Error::new(day, "input was parsed by another puzzle"))
+}
+
+/// Parses a non-empty rectangular grid of decimal digits.
+fn parse_digits(day: u8, input: &str) -> Result<Vec<Vec<u8>>> {
+    let mut width = None;
+    let grid: Vec<Vec<u8>> = input
+        .lines()
+        .enumerate()
+        .map(|(i, s)| {
+            let err = || Error::new(day, "invalid row").at(i + 1, s);
+            if s.is_empty() || *width.get_or_insert(s.len()) != s.len() {
+                return Err(err());
+            }
+            s.bytes()
+                .map(|b| match b {
+                    b'0'..=b'9' => Ok(b - 48),
+                    _ => Err(err()),
+                })
+                .collect()
+        })
+        .collect::<Result<_>>()?;
+    if grid.is_empty() {
+        return Err(Error::new(day, "empty grid"));
+    }
+    Ok(grid)
+}
+
+/// Where the input of a puzzle is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    /// The file `NN-name.txt` in a directory, e.g. `inputs/01-input.txt`.
+    Dir { dir: PathBuf, name: String },
+    /// A single file, whatever the day.
+    File(PathBuf),
+    /// The standard input.
+    Stdin,
+}
+
+impl Source {
+    /// The named files in [`input_dir`].
+    pub fn named(name: &str) -> Source {
+        Source::Dir {
+            dir: input_dir(),
+            name: name.to_string(),
+        }
+    }
+
+    /// The files of expected answers next to the inputs: `answers.txt`
+    /// for the real inputs, and `NN-NAME.answers` next to the input of
+    /// every day for the others, as the examples have them.
+    pub fn answer_files(&self) -> Option<AnswerFiles> {
+        match self {
+            Source::Dir { dir, name } if name == "input" => {
+                Some(AnswerFiles::Single(dir.join("answers.txt")))
+            }
+            Source::Dir { dir, name } => Some(AnswerFiles::PerDay {
+                dir: dir.clone(),
+                name: name.clone(),
+            }),
+            _ => None,
+        }
+    }
+}
+
+/// Returns `$AOC_INPUT_DIR` if set, or else `inputs`.
+pub fn input_dir() -> PathBuf {
+    env::var_os("AOC_INPUT_DIR")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("inputs"))
+}
+
+pub fn read_input(day: u8, source: &Source) -> Result<String> {
+    let path = match source {
+        Source::Dir { dir, name } => {
+            dir.join(format!("{:02}-{}.txt", day, name))
+        }
+        Source::File(path) => path.clone(),
+        Source::Stdin => {
+            let mut input = String::new();
+            return match io::stdin().read_to_string(&mut input) {
+                Ok(_) => Ok(input),
+                Err(err) => {
+                    let msg = format!("cannot read standard input: {}", err);
+                    Err(Error::new(day, msg))
+                }
+            };
+        }
+    };
+    fs::read_to_string(&path).map_err(|err| {
+        let msg = format!("cannot read {}: {}", path.display(), err);
+        Error::new(day, msg)
+    })
+}
+
+pub fn read_as_string(day: u8, filename: &str) -> Result<String> {
+    read_input(day, &Source::named(filename))
+}
+
+/// Reads the example of a puzzle, panicking if it is missing.
 pub fn read_example(day: u8) -> String {
-    read_as_string(day, "example")
+    read_as_string(day, "example").unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_ordered_by_day() {
+        let days: Vec<u8> = registry().iter().map(|s| s.day()).collect();
+        assert_eq!(days, (1..=25).collect::<Vec<u8>>());
+        assert_eq!(solution(19).map(|s| s.title()), Some("Beacon Scanner"));
+        assert!(solution(0).is_none());
+        assert!(solution(26).is_none());
+    }
+
+    #[test]
+    fn parsed_input_of_another_day() {
+        let input = solution(1).unwrap().parse("1\n2\n").unwrap();
+        let err = solution(2).unwrap().part_one(&input).err().unwrap();
+        assert_eq!(err.day, 2);
+    }
+
+    #[test]
+    fn read_from_source() {
+        let dir = Source::Dir {
+            dir: PathBuf::from("inputs"),
+            name: "example".to_string(),
+        };
+        let file = Source::File(PathBuf::from("inputs/06-example.txt"));
+        assert_eq!(read_input(6, &dir).unwrap(), "3,4,3,1,2\n");
+        assert_eq!(read_input(6, &file).unwrap(), "3,4,3,1,2\n");
+
+        let err = read_input(26, &dir).unwrap_err();
+        assert_eq!(err.day, 26);
+        assert!(err.message.contains("26-example.txt"));
+    }
 }
@@ -1,67 +1,551 @@
 use std::env;
-use std::fmt::Display;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+use std::process;
+use std::time::{Duration, Instant};
+
+use aoc::answers::{AnswerFiles, Answers, Check};
+use aoc::bench::{self, Report, Runs, Stats};
+use aoc::day16::{self, bits::BitReader, Packets};
+use aoc::day23::{self, Burrow, Game, Move};
+use aoc::pool;
+use aoc::record::Record;
+use aoc::select::{self, Selection};
+use aoc::{Error, Source};
+
+const USAGE: &str = "\
+Usage: aoc [OPTIONS] [DAY | DAY:PART | FIRST-LAST]...
+       aoc packet <HEX | ->
+       aoc amphipod [--example | FILE] [--unfold]
+
+Solves the puzzles of the given days, or of every day.
+
+Examples:
+  aoc 3-7             days 3 to 7
+  aoc 5:2 --example   part two of day 5, on its example
+  aoc --part 1        part one of every day
+  aoc packet D2FE28   the tree and the expression of day 16 packets
+  aoc amphipod        sort the day 23 amphipods by hand
+
+Options:
+  --part <1|2>        solve only one part of each day
+  --example           read the examples instead of the inputs
+  --inputs <dir>      read the inputs from a directory [env: AOC_INPUT_DIR]
+  --file <path>       read the input of a single day from a file
+  -                   read the input of a single day from the standard input
+  --format <format>   print text, json or csv
+  --jobs <n>          solve the days on n threads
+  --bench             time each phase of the puzzles repeatedly
+  --runs <n>          run each phase n times when benchmarking
+  --check             compare the answers with the expected ones
+  --record            save the answers as the expected ones
+  --answers <path>    the file of expected answers
+  --list              list the puzzles
+  -h, --help          print this help";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+}
 
 fn main() {
-    macro_rules! puzzle {
-        ($mod:ident, $title:expr) => {
-            (
-                $title,
-                |input| Box::new(aoc::$mod::part_one(input)),
-                |input| Box::new(aoc::$mod::part_two(input)),
-            )
-        };
+    let mut format = Format::Text;
+    let mut name = "input";
+    let mut dir: Option<PathBuf> = None;
+    let mut file: Option<Source> = None;
+    let mut bench = false;
+    let mut check = false;
+    let mut record_answers = false;
+    let mut answers_path: Option<PathBuf> = None;
+    let mut runs = Runs::default();
+    let mut jobs = 1;
+    let mut part = None;
+    let mut days: Vec<Selection> = Vec::new();
+
+    let mut args = env::args().skip(1).peekable();
+    if args.peek().map(String::as_str) == Some("packet") {
+        args.next();
+        return packet(args.next(), args.next());
+    }
+    if args.peek().map(String::as_str) == Some("amphipod") {
+        args.next();
+        return amphipod(args);
+    }
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--example" => name = "example",
+            "--inputs" => match args.next() {
+                Some(path) => dir = Some(PathBuf::from(path)),
+                None => usage("--inputs expects a directory"),
+            },
+            "--file" => match args.next() {
+                Some(path) => file = Some(Source::File(PathBuf::from(path))),
+                None => usage("--file expects a path"),
+            },
+            "-" => file = Some(Source::Stdin),
+            "--format" => match args.next().as_deref() {
+                Some("text") => format = Format::Text,
+                Some("json") => format = Format::Json,
+                Some("csv") => format = Format::Csv,
+                _ => usage("--format expects text, json or csv"),
+            },
+            "--bench" => bench = true,
+            "--check" => check = true,
+            "--record" => record_answers = true,
+            "--answers" => match args.next() {
+                Some(path) => answers_path = Some(PathBuf::from(path)),
+                None => usage("--answers expects a path"),
+            },
+            "--jobs" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) if n > 0 => jobs = n,
+                _ => usage("--jobs expects a positive number"),
+            },
+            "--runs" => match args.next().and_then(|n| n.parse().ok()) {
+                Some(n) if n > 0 => runs = Runs::exactly(n),
+                _ => usage("--runs expects a positive number"),
+            },
+            "--part" => match args.next().map(|p| select::parse_part(&p)) {
+                Some(Ok(p)) => part = Some(p),
+                Some(Err(err)) => usage(&err),
+                None => usage("--part expects 1 or 2"),
+            },
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            "--list" => {
+                for solution in aoc::registry() {
+                    println!("{:>2}  {}", solution.day(), solution.title());
+                }
+                return;
+            }
+            _ if arg.starts_with('-') => {
+                usage(&format!("unknown option {}", arg))
+            }
+            _ => match select::parse_selection(&arg) {
+                Ok(selection) => days.extend(selection),
+                Err(err) => usage(&err),
+            },
+        }
     }
 
-    type SolverFn = fn(&str) -> Box<dyn Display>;
-
-    let puzzles: Vec<(&str, SolverFn, SolverFn)> = vec![
-        puzzle!(day01, "Sonar Sweep"),
-        puzzle!(day02, "Dive!"),
-        puzzle!(day03, "Binary Diagnostic"),
-        puzzle!(day04, "Giant Squid"),
-        puzzle!(day05, "Hydrothermal Venture"),
-        puzzle!(day06, "Lanternfish"),
-        puzzle!(day07, "The Treachery of Whales"),
-        puzzle!(day08, "Seven Segment Search"),
-        puzzle!(day09, "Smoke Basin"),
-        puzzle!(day10, "Syntax Scoring"),
-        puzzle!(day11, "Dumbo Octopus"),
-        puzzle!(day12, "Passage Pathing"),
-        puzzle!(day13, "Transparent Origami"),
-        puzzle!(day14, "Extended Polymerization"),
-        puzzle!(day15, "Chiton"),
-        puzzle!(day16, "Packet Decoder"),
-        puzzle!(day17, "Trick Shot"),
-        puzzle!(day18, "Snailfish"),
-        puzzle!(day19, "Beacon Scanner"),
-        puzzle!(day20, "Trench Map"),
-        puzzle!(day21, "Dirac Dice"),
-        puzzle!(day22, "Reactor Reboot"),
-        puzzle!(day23, "Amphipod"),
-        puzzle!(day24, "Arithmetic Logic Unit"),
-        puzzle!(day25, "Sea Cucumber"),
-    ];
+    let source = match file {
+        Some(_) if days.len() != 1 => {
+            usage("--file and - read the input of exactly one day")
+        }
+        Some(source) => source,
+        None => Source::Dir {
+            dir: dir.unwrap_or_else(aoc::input_dir),
+            name: name.to_string(),
+        },
+    };
+
+    if days.is_empty() {
+        let registry = aoc::registry().iter();
+        days = registry.map(|s| Selection { day: s.day(), part }).collect();
+    }
+    for selection in days.iter_mut() {
+        selection.part = selection.part.or(part);
+    }
+
+    if (check || record_answers) && bench {
+        usage("--bench cannot be used with --check or --record");
+    }
+    if bench && jobs > 1 {
+        usage("--bench runs one day at a time and cannot use --jobs");
+    }
+    if format != Format::Text && (bench || check || record_answers) {
+        usage("--format cannot be used with --bench, --check or --record");
+    }
+    let answer_files = answers_path.map(AnswerFiles::Single);
+    let answer_files = match answer_files.or_else(|| source.answer_files()) {
+        Some(files) => files,
+        None if check || record_answers => {
+            let flag = if check { "--check" } else { "--record" };
+            usage(&format!(
+                "{} with --file or stdin needs --answers PATH",
+                flag
+            ))
+        }
+        None => AnswerFiles::Single(PathBuf::new()),
+    };
+    let mut answers = Answers::default();
+    if check || record_answers {
+        let selected = days.iter().map(|s| s.day);
+        match answer_files.read(selected, record_answers) {
+            Ok(read) => answers = read,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                process::exit(1);
+            }
+        }
+    }
+    let mut checks = [0_u32; 3];
 
-    let filename = match env::args().find(|a| a == "--example") {
-        None => "input",
-        Some(_) => "example",
+    if bench {
+        if !run_bench(days, &source, runs) {
+            process::exit(1);
+        }
+        return;
+    }
+
+    let mut failed = 0;
+    match format {
+        Format::Json => println!("["),
+        Format::Csv => println!("{}", Record::CSV_HEADER),
+        Format::Text => {}
+    }
+    let start = Instant::now();
+    let mut busy = Duration::ZERO;
+    let mut records = 0;
+    let solve = |selection: Selection| {
+        let Selection { day, part } = selection;
+        let solution = aoc::solution(day).expect("a validated day");
+        match aoc::read_input(day, &source) {
+            Ok(input) => Record::run(solution, &input, part),
+            Err(err) => Record::failed(solution, Some(err)),
+        }
     };
+    let selected: Vec<u8> = days.iter().map(|s| s.day).collect();
+    pool::map_ordered(jobs, days, solve, |record: Result<Record, String>| {
+        let record = record.unwrap_or_else(|message| {
+            let day = selected[records];
+            let solution = aoc::solution(day).expect("a validated day");
+            let message = format!("panicked: {}", message);
+            Record::failed(solution, Some(Error::new(day, message)))
+        });
+        busy += record.parse.unwrap_or_default();
+        busy += record.solve.unwrap_or_default();
+        if record.error.is_some() {
+            failed += 1;
+        }
+        match format {
+            Format::Json if records > 0 => print!(",\n{}", record.to_json()),
+            Format::Json => print!("{}", record.to_json()),
+            Format::Csv => println!("{}", record.to_csv()),
+            Format::Text if check => {
+                print_checked(&record, &answers, &mut checks)
+            }
+            Format::Text => print_text(&record),
+        }
+        if record_answers {
+            for (part, answer) in (1..).zip(&record.answers) {
+                if let Some(answer) = answer {
+                    answers.insert(record.day, part, answer);
+                }
+            }
+        }
+        records += 1;
+    });
+    if format == Format::Json {
+        println!("{}]", if records > 0 { "\n" } else { "" });
+    }
+    if records > 1 {
+        let wall = start.elapsed();
+        let timing = format!(
+            "Time: {} wall, {} summed over {} days, {} job{}",
+            duration(wall),
+            duration(busy),
+            records,
+            jobs,
+            if jobs == 1 { "" } else { "s" }
+        );
+        match format {
+            Format::Text => println!("{}", timing),
+            _ => eprintln!("{}", timing),
+        }
+    }
 
-    let mut days: Vec<usize> =
-        env::args().filter_map(|a| a.parse().ok()).collect();
+    if check {
+        let [pass, fail, missing] = checks;
+        println!(
+            "Check: {} passed, {} failed, {} missing",
+            pass, fail, missing
+        );
+        failed += fail;
+    }
 
-    if days.is_empty() {
-        days = (1..=puzzles.len()).collect();
+    if record_answers {
+        if let Err(err) = answer_files.write(&answers) {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+        println!("Recorded answers to {}", answer_files);
     }
 
-    for day in days {
-        let (title, part1, part2) = &puzzles[day - 1];
-        let input = aoc::read_as_string(day as u8, filename);
-        let input = input.as_str();
+    if failed > 0 {
+        process::exit(1);
+    }
+}
+
+/// Prints the decoded packet of a day 16 transmission.
+fn packet(hex: Option<String>, extra: Option<String>) {
+    let hex = match (hex, extra) {
+        (Some(hex), None) if hex == "-" => {
+            aoc::read_input(16, &Source::Stdin).unwrap_or_else(|err| {
+                eprintln!("error: {}", err);
+                process::exit(1);
+            })
+        }
+        (Some(hex), None) => hex,
+        _ => usage("packet expects a single hexadecimal transmission"),
+    };
+    let (data, len) = day16::bits::hex_to_bytes(&hex).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        process::exit(1);
+    });
+    let mut packets = Packets::new(BitReader::with_len(&data, len));
+    let mut first = true;
+    while let Some(decoded) = packets.next_spanned() {
+        match decoded {
+            Ok((packet, span)) => {
+                if !first {
+                    println!();
+                }
+                first = false;
+                print!("{}", day16::tree(&packet, Some(&span)));
+                println!();
+                println!("{}", packet);
+            }
+            Err(err) => {
+                eprintln!("error: {}", err);
+                process::exit(1);
+            }
+        }
+    }
+}
 
-        println!("--- Day {}: {} ---", day, title);
-        println!("Part One: {}", part1(input));
-        println!("Part Two: {}", part2(input));
+const AMPHIPOD_HELP: &str = "\
+Commands:
+  <n>     play the move numbered n
+  u       take the last move back
+  b       show the best moves from here
+  q       quit";
+
+/// Sorts the amphipods of a day 23 burrow with moves read from the
+/// standard input.
+fn amphipod(args: impl Iterator<Item = String>) {
+    let mut source = Source::named("input");
+    let mut unfold = false;
+    for arg in args {
+        match arg.as_str() {
+            "--example" => source = Source::named("example"),
+            "--unfold" => unfold = true,
+            _ if arg.starts_with('-') => {
+                usage(&format!("unknown option {}", arg))
+            }
+            _ => source = Source::File(PathBuf::from(arg)),
+        }
+    }
+    let burrow = aoc::read_input(23, &source)
+        .and_then(|input| Burrow::parse(&input))
+        .and_then(|burrow| match unfold {
+            true => day23::unfold(&burrow),
+            false => Ok(burrow),
+        })
+        .unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        });
+
+    let mut game = Game::new(burrow);
+    let mut lines = io::stdin().lock().lines();
+    println!("{}", AMPHIPOD_HELP);
+    loop {
         println!();
+        print!("{}", game.render());
+        println!("Energy: {}", game.energy());
+        if game.is_sorted() {
+            println!("Sorted!");
+            return;
+        }
+        let moves = game.moves();
+        if moves.is_empty() {
+            println!("No move left, take one back with u");
+        }
+        for (i, m) in (1..).zip(&moves) {
+            println!("{:>3}) {}", i, describe(game.burrow(), m));
+        }
+        print!("> ");
+        let _ = io::stdout().flush();
+        let line = match lines.next() {
+            Some(Ok(line)) => line,
+            _ => return,
+        };
+        match line.trim() {
+            "q" => return,
+            "u" => {
+                if game.undo().is_none() {
+                    println!("No move to take back");
+                }
+            }
+            "b" => match game.best() {
+                Some(plan) => {
+                    for m in &plan.moves {
+                        println!("     {}", describe(game.burrow(), m));
+                    }
+                    println!(
+                        "{} energy from here, {} in all",
+                        plan.energy,
+                        game.energy() + plan.energy
+                    );
+                }
+                None => println!("The amphipods cannot be sorted any more"),
+            },
+            n => match n.parse::<usize>() {
+                Ok(n) if (1..=moves.len()).contains(&n) => {
+                    game.play(moves[n - 1]);
+                }
+                _ => println!("{}", AMPHIPOD_HELP),
+            },
+        }
+    }
+}
+
+fn describe(burrow: &Burrow, m: &Move) -> String {
+    format!(
+        "{} from {} to {}, {} energy",
+        day23::burrow::letter(m.amphipod),
+        burrow.place(m.from),
+        burrow.place(m.to),
+        m.energy
+    )
+}
+
+fn usage(message: &str) -> ! {
+    eprintln!("error: {}\n\n{}", message, USAGE);
+    process::exit(2);
+}
+
+fn print_text(record: &Record) {
+    println!("--- Day {}: {} ---", record.day, record.title);
+    if let Some(err) = &record.error {
+        match record.solve {
+            Some(_) => println!("Answers: failed"),
+            None => println!("Input: failed"),
+        }
+        eprintln!("error: {}", err);
+    }
+    for (name, answer) in ["One", "Two"].iter().zip(&record.answers) {
+        if let Some(answer) = answer {
+            println!("Part {}: {}", name, answer);
+        }
     }
+    println!();
+}
+
+/// Prints the answers along with whether they are the expected ones.
+fn print_checked(record: &Record, answers: &Answers, checks: &mut [u32; 3]) {
+    if record.error.is_some() {
+        return print_text(record);
+    }
+    println!("--- Day {}: {} ---", record.day, record.title);
+    for (part, answer) in (1..).zip(&record.answers) {
+        let answer = match answer {
+            Some(answer) => answer,
+            None => continue,
+        };
+        let name = if part == 1 { "One" } else { "Two" };
+        let result = answers.check(record.day, part, answer);
+        checks[result as usize] += 1;
+        match (result, answers.get(record.day, part)) {
+            (Check::Fail, Some(expected)) => println!(
+                "Part {} [{}]: {} (expected {})",
+                name, result, answer, expected
+            ),
+            _ => println!("Part {} [{}]: {}", name, result, answer),
+        }
+    }
+    println!();
+}
+
+/// Benchmarks every day, returning whether they all succeeded.
+fn run_bench(days: Vec<Selection>, source: &Source, runs: Runs) -> bool {
+    let mut reports = Vec::new();
+    let mut failed = false;
+    for Selection { day, part } in days {
+        let solution = aoc::solution(day).expect("a validated day");
+        println!("--- Day {}: {} ---", day, solution.title());
+        let input = aoc::read_input(day, source);
+        let bench =
+            |input: String| bench::bench(solution, &input, part, runs);
+        match input.and_then(bench) {
+            Ok(report) => {
+                print_report(&report);
+                reports.push(report);
+            }
+            Err(err) => {
+                println!("Bench: failed");
+                eprintln!("error: {}", err);
+                failed = true;
+            }
+        }
+        println!();
+    }
+    print_summary(&mut reports);
+    !failed
+}
+
+fn print_report(report: &Report) {
+    println!(
+        "{:<9} {:>10} {:>10} {:>10} {:>5}",
+        "Phase", "Min", "Median", "Mean", "Runs"
+    );
+    let phases = [
+        ("Parse", report.parse),
+        ("Part One", report.part_one),
+        ("Part Two", report.part_two),
+    ];
+    for (name, stats) in phases.into_iter().filter(|(_, s)| s.runs > 0) {
+        println!(
+            "{:<9} {:>10} {:>10} {:>10} {:>5}",
+            name,
+            duration(stats.min),
+            duration(stats.median),
+            duration(stats.mean),
+            stats.runs
+        );
+    }
+}
+
+/// Prints the days ordered from the slowest, by their median times.
+fn print_summary(reports: &mut [Report]) {
+    let total: Duration = reports.iter().map(Report::total).sum();
+    reports.sort_by_key(|r| std::cmp::Reverse(r.total()));
+
+    println!("--- Slowest Days ---");
+    println!(
+        "{:>3}  {:<30} {:>10} {:>10} {:>10} {:>10} {:>6}",
+        "Day", "Title", "Parse", "Part One", "Part Two", "Total", "Share"
+    );
+    for report in reports.iter() {
+        let share = match total.as_secs_f64() {
+            t if t > 0.0 => report.total().as_secs_f64() / t * 100.0,
+            _ => 0.0,
+        };
+        println!(
+            "{:>3}  {:<30} {:>10} {:>10} {:>10} {:>10} {:>5.1}%",
+            report.day,
+            report.title,
+            median(report.parse),
+            median(report.part_one),
+            median(report.part_two),
+            duration(report.total()),
+            share
+        );
+    }
+    println!("{:>3}  {:<30} {:>43}", "", "Total", duration(total));
+}
+
+fn median(stats: Stats) -> String {
+    match stats.runs {
+        0 => "-".to_string(),
+        _ => duration(stats.median),
+    }
+}
+
+fn duration(d: Duration) -> String {
+    format!("{:.2?}", d)
 }
@@ -0,0 +1,107 @@
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::thread;
+
+/// Applies `f` to every item on `jobs` threads, handing the results to
+/// `emit` in the order of the items, each as soon as it and all the ones
+/// before it are ready. If `f` panics on an item, its result is the
+/// message of the panic, and the other items are still handed over.
+pub fn map_ordered<T, R>(
+    jobs: usize,
+    items: Vec<T>,
+    f: impl Fn(T) -> R + Sync,
+    mut emit: impl FnMut(Result<R, String>),
+) where
+    T: Send,
+    R: Send,
+{
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let (sender, receiver) = mpsc::channel();
+    thread::scope(|scope| {
+        for _ in 0..jobs.max(1) {
+            let sender = sender.clone();
+            let (queue, f) = (&queue, &f);
+            scope.spawn(move || loop {
+                let next = queue.lock().unwrap().next();
+                let (i, item) = match next {
+                    Some(next) => next,
+                    None => break,
+                };
+                let result =
+                    panic::catch_unwind(AssertUnwindSafe(|| f(item)));
+                let result =
+                    result.map_err(|payload| panic_message(&*payload));
+                if sender.send((i, result)).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut next = 0;
+        for (i, result) in receiver {
+            pending.insert(i, result);
+            while let Some(result) = pending.remove(&next) {
+                emit(result);
+                next += 1;
+            }
+        }
+    });
+}
+
+/// The message a panic was raised with, if it has one.
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    match payload.downcast_ref::<&str>() {
+        Some(message) => message.to_string(),
+        None => match payload.downcast_ref::<String>() {
+            Some(message) => message.clone(),
+            None => "panicked".to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn ordered() {
+        let items: Vec<u64> = (0..20).collect();
+        let mut results = Vec::new();
+        map_ordered(
+            4,
+            items,
+            |i| {
+                thread::sleep(Duration::from_millis(20 - i));
+                i * i
+            },
+            |r| results.push(r.unwrap()),
+        );
+        let expected: Vec<u64> = (0..20).map(|i| i * i).collect();
+        assert_eq!(results, expected);
+    }
+
+    #[test]
+    fn panicking_item() {
+        let items: Vec<u64> = (0..10).collect();
+        let mut results = Vec::new();
+        map_ordered(
+            3,
+            items,
+            |i| match i {
+                4 => panic!("item {}", i),
+                _ => i,
+            },
+            |r| results.push(r),
+        );
+        let mut expected: Vec<Result<u64, String>> =
+            (0..10).map(Ok).collect();
+        expected[4] = Err("item 4".to_string());
+        assert_eq!(results, expected);
+    }
+}
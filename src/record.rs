@@ -0,0 +1,191 @@
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+use crate::{Error, Solution};
+
+/// The outcome of solving the puzzle of one day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub day: u8,
+    pub title: &'static str,
+    /// The answers to the parts that were solved.
+    pub answers: [Option<String>; 2],
+    /// How long parsing took, if the input could be read.
+    pub parse: Option<Duration>,
+    /// How long solving took, if the input could be parsed.
+    pub solve: Option<Duration>,
+    pub error: Option<Error>,
+}
+
+impl Record {
+    /// Parses the input and solves one part, or both if `part` is `None`,
+    /// timing each phase.
+    pub fn run(
+        solution: &dyn Solution,
+        input: &str,
+        part: Option<u8>,
+    ) -> Record {
+        let mut record = Record::failed(solution, None);
+        let now = Instant::now();
+        let parsed = solution.parse(input);
+        record.parse = Some(now.elapsed());
+        let parsed = match parsed {
+            Ok(parsed) => parsed,
+            Err(err) => {
+                record.error = Some(err);
+                return record;
+            }
+        };
+        let now = Instant::now();
+        let answers = match part {
+            Some(1) => solution.part_one(&parsed).map(|a| (Some(a), None)),
+            Some(_) => solution.part_two(&parsed).map(|a| (None, Some(a))),
+            None => solution.solve(&parsed).map(|(a, b)| (Some(a), Some(b))),
+        };
+        record.solve = Some(now.elapsed());
+        match answers {
+            Ok((one, two)) => {
+                record.answers = [
+                    one.map(|answer| answer.to_string()),
+                    two.map(|answer| answer.to_string()),
+                ]
+            }
+            Err(err) => record.error = Some(err),
+        }
+        record
+    }
+
+    /// A puzzle whose input could not be read.
+    pub fn failed(solution: &dyn Solution, error: Option<Error>) -> Record {
+        Record {
+            day: solution.day(),
+            title: solution.title(),
+            answers: [None, None],
+            parse: None,
+            solve: None,
+            error,
+        }
+    }
+
+    fn answer(&self, part: usize) -> Option<&str> {
+        self.answers[part].as_deref()
+    }
+
+    pub const CSV_HEADER: &'static str =
+        "day,title,part_one,part_two,parse_ns,solve_ns,error";
+
+    /// Formats the record as a line of CSV, under [`Record::CSV_HEADER`].
+    pub fn to_csv(&self) -> String {
+        let error = self.error.as_ref().map(Error::to_string);
+        let fields = [
+            self.day.to_string(),
+            csv_field(self.title),
+            csv_field(self.answer(0).unwrap_or_default()),
+            csv_field(self.answer(1).unwrap_or_default()),
+            self.parse.map(nanos).unwrap_or_default(),
+            self.solve.map(nanos).unwrap_or_default(),
+            csv_field(error.as_deref().unwrap_or_default()),
+        ];
+        fields.join(",")
+    }
+
+    /// Formats the record as a JSON object, on a single line.
+    pub fn to_json(&self) -> String {
+        let error = self.error.as_ref().map(Error::to_string);
+        format!(
+            "{{\"day\":{},\"title\":{},\"part_one\":{},\"part_two\":{},\
+             \"parse_ns\":{},\"solve_ns\":{},\"error\":{}}}",
+            self.day,
+            json_string(self.title),
+            json_option(self.answer(0)),
+            json_option(self.answer(1)),
+            self.parse.map(nanos).unwrap_or_else(|| "null".to_string()),
+            self.solve.map(nanos).unwrap_or_else(|| "null".to_string()),
+            json_option(error.as_deref()),
+        )
+    }
+}
+
+fn nanos(duration: Duration) -> String {
+    duration.as_nanos().to_string()
+}
+
+/// Quotes a field if it contains a separator, a quote or a line break.
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn json_option(s: Option<&str>) -> String {
+    s.map_or_else(|| "null".to_string(), json_string)
+}
+
+fn json_string(s: &str) -> String {
+    let mut json = String::with_capacity(s.len() + 2);
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(json, "\\u{:04x}", c as u32);
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{read_example, solution};
+
+    #[test]
+    fn run() {
+        let day13 = solution(13).unwrap();
+        let record = Record::run(day13, &read_example(13), None);
+        let [one, two] = record.answers;
+        assert_eq!(one.as_deref(), Some("17"));
+        assert!(two.unwrap().contains('\n'));
+        assert!(record.error.is_none());
+
+        let record = Record::run(day13, &read_example(13), Some(1));
+        assert_eq!(record.answers, [Some("17".to_string()), None]);
+
+        let record = Record::run(solution(1).unwrap(), "x", None);
+        assert_eq!(record.answers, [None, None]);
+        assert!(record.parse.is_some());
+        assert!(record.solve.is_none());
+        assert_eq!(record.error.unwrap().line, Some(1));
+    }
+
+    #[test]
+    fn formats() {
+        let record = Record {
+            day: 2,
+            title: "Dive!",
+            answers: [
+                Some("150".to_string()),
+                Some("a \"b\",\nc".to_string()),
+            ],
+            parse: Some(Duration::from_nanos(1200)),
+            solve: None,
+            error: None,
+        };
+        assert_eq!(
+            record.to_json(),
+            "{\"day\":2,\"title\":\"Dive!\",\"part_one\":\"150\",\
+             \"part_two\":\"a \\\"b\\\",\\nc\",\"parse_ns\":1200,\
+             \"solve_ns\":null,\"error\":null}"
+        );
+        assert_eq!(record.to_csv(), "2,Dive!,150,\"a \"\"b\"\",\nc\",1200,,");
+    }
+}
@@ -0,0 +1,68 @@
+use crate::registry;
+
+/// A day, and one of its parts or both, picked on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Selection {
+    pub day: u8,
+    /// Either 1 or 2, or `None` for both parts.
+    pub part: Option<u8>,
+}
+
+/// Parses a day `5`, a part of a day `5:2` or a range of days `3-7`.
+pub fn parse_selection(arg: &str) -> Result<Vec<Selection>, String> {
+    let (days, part) = match arg.split_once(':') {
+        Some((days, part)) => (days, Some(parse_part(part)?)),
+        None => (arg, None),
+    };
+    let (first, last) = match days.split_once('-') {
+        Some((first, last)) => (parse_day(first)?, parse_day(last)?),
+        None => (parse_day(days)?, parse_day(days)?),
+    };
+    if first > last {
+        return Err(format!("invalid range of days: {}", days));
+    }
+    if part.is_some() && first != last {
+        return Err(format!("a part needs a single day: {}", arg));
+    }
+    Ok((first..=last).map(|day| Selection { day, part }).collect())
+}
+
+pub fn parse_day(s: &str) -> Result<u8, String> {
+    match s.parse() {
+        Ok(day) if registry().iter().any(|s| s.day() == day) => Ok(day),
+        _ => Err(format!("no puzzle for day {}", s)),
+    }
+}
+
+pub fn parse_part(s: &str) -> Result<u8, String> {
+    match s {
+        "1" => Ok(1),
+        "2" => Ok(2),
+        _ => Err(format!("invalid part {}, expected 1 or 2", s)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn days(arg: &str) -> Vec<(u8, Option<u8>)> {
+        let selection = parse_selection(arg).unwrap();
+        selection.iter().map(|s| (s.day, s.part)).collect()
+    }
+
+    #[test]
+    fn selections() {
+        assert_eq!(days("5"), [(5, None)]);
+        assert_eq!(days("5:2"), [(5, Some(2))]);
+        assert_eq!(days("3-5"), [(3, None), (4, None), (5, None)]);
+        assert_eq!(days("25-25"), [(25, None)]);
+    }
+
+    #[test]
+    fn invalid_selections() {
+        for arg in ["0", "26", "x", "7-3", "5:3", "5:", "3-7:1", "-", "1-"] {
+            assert!(parse_selection(arg).is_err(), "{}", arg);
+        }
+    }
+}
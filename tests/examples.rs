@@ -0,0 +1,83 @@
+//! Runs every `inputs/NN-example*.txt` that has an adjacent
+//! `NN-example*.answers` file through the puzzle of day `NN`, and compares
+//! the answers with the expected ones.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use aoc::answers::{Answers, Check};
+use aoc::record::Record;
+
+/// Returns the day and the path of every example with expected answers.
+fn examples(dir: &Path) -> Vec<(u8, PathBuf, PathBuf)> {
+    let mut examples = Vec::new();
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        let name = match path.file_name().and_then(|s| s.to_str()) {
+            Some(name) => name,
+            None => continue,
+        };
+        let day = match name.split_once('-') {
+            Some((day, rest)) if rest.starts_with("example") => day,
+            _ => continue,
+        };
+        let stem = match name.strip_suffix(".txt") {
+            Some(stem) => stem,
+            None => continue,
+        };
+        let answers = dir.join(format!("{}.answers", stem));
+        if let (Ok(day), true) = (day.parse(), answers.exists()) {
+            examples.push((day, path, answers));
+        }
+    }
+    examples.sort();
+    examples
+}
+
+#[test]
+fn examples_have_the_expected_answers() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+    let examples = examples(&dir);
+    assert!(!examples.is_empty(), "no examples in {}", dir.display());
+
+    let mut failures = Vec::new();
+    for (day, path, answers) in examples {
+        let name = path.file_name().unwrap().to_string_lossy();
+        let solution = match aoc::solution(day) {
+            Some(solution) => solution,
+            None => {
+                failures.push(format!("{}: no puzzle for day {}", name, day));
+                continue;
+            }
+        };
+        let input = fs::read_to_string(&path).unwrap();
+        let expected = fs::read_to_string(&answers).unwrap();
+        let expected = Answers::parse_day(day, &expected);
+
+        for part in 1..=2 {
+            if expected.get(day, part).is_none() {
+                continue;
+            }
+            let record = Record::run(solution, &input, Some(part));
+            let answer = record.answers[part as usize - 1].as_deref();
+            match (answer, &record.error) {
+                (_, Some(err)) => {
+                    failures.push(format!("{} part {}: {}", name, part, err))
+                }
+                (Some(answer), None) => {
+                    if expected.check(day, part, answer) != Check::Pass {
+                        failures.push(format!(
+                            "{} part {}: got {:?}, expected {:?}",
+                            name,
+                            part,
+                            answer,
+                            expected.get(day, part).unwrap_or_default()
+                        ));
+                    }
+                }
+                (None, None) => unreachable!(),
+            }
+        }
+    }
+    assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+}